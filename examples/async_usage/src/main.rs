@@ -4,6 +4,8 @@
 //
 // Author: Simon Brummer (simon.brummer@posteo.de)
 
+#![allow(clippy::println_empty_string)]
+
 use std::str::FromStr;
 use std::thread::sleep;
 use std::time::Duration;
@@ -0,0 +1,133 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Module containing types returned by the mempool.space address endpoints.
+
+// Imports
+use super::transaction::TxStatus;
+use serde::Deserialize;
+
+/// Address summary as returned by `GET /address/:address` and `GET /scripthash/:hash`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Address {
+    /// The queried address or scripthash
+    #[serde(alias = "scripthash")]
+    pub address: String,
+    /// Statistics over all confirmed transactions
+    pub chain_stats: AddressStats,
+    /// Statistics over all unconfirmed transactions
+    pub mempool_stats: AddressStats,
+}
+
+impl Address {
+    /// Get the confirmed balance in satoshis.
+    pub fn confirmed_balance(&self) -> i64 {
+        self.chain_stats.balance()
+    }
+
+    /// Get the balance in satoshis including unconfirmed transactions.
+    pub fn total_balance(&self) -> i64 {
+        self.chain_stats.balance() + self.mempool_stats.balance()
+    }
+}
+
+/// Funding and spending statistics of an [Address].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AddressStats {
+    /// Number of outputs funding the address
+    pub funded_txo_count: u64,
+    /// Sum of all outputs funding the address in satoshis
+    pub funded_txo_sum: u64,
+    /// Number of outputs spent from the address
+    pub spent_txo_count: u64,
+    /// Sum of all outputs spent from the address in satoshis
+    pub spent_txo_sum: u64,
+    /// Number of transactions involving the address
+    pub tx_count: u64,
+}
+
+impl AddressStats {
+    /// Get the balance change in satoshis. Mempool statistics may yield a negative value.
+    pub fn balance(&self) -> i64 {
+        self.funded_txo_sum as i64 - self.spent_txo_sum as i64
+    }
+}
+
+/// Unspent output of an [Address] as returned by `GET /address/:address/utxo`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Utxo {
+    /// Transaction id of the transaction creating the output
+    pub txid: String,
+    /// Index of the output
+    pub vout: u32,
+    /// Confirmation status of the transaction creating the output
+    pub status: TxStatus,
+    /// Output value in satoshis
+    pub value: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn address_balance() {
+        // Expectency: Balances must be derived from the funded and spent sums, unconfirmed
+        //             spends are allowed to lower the total balance.
+        let json = r#"{
+            "address": "1wiz18xYmhRX6xStj2b9t1rwWX4GKUgpv",
+            "chain_stats": {
+                "funded_txo_count": 5,
+                "funded_txo_sum": 15007599040,
+                "spent_txo_count": 5,
+                "spent_txo_sum": 15007000000,
+                "tx_count": 7
+            },
+            "mempool_stats": {
+                "funded_txo_count": 0,
+                "funded_txo_sum": 0,
+                "spent_txo_count": 1,
+                "spent_txo_sum": 9040,
+                "tx_count": 1
+            }
+        }"#;
+        let address: Address = serde_json::from_str(json).unwrap();
+        assert_eq!(address.confirmed_balance(), 599040);
+        assert_eq!(address.total_balance(), 590000);
+    }
+
+    #[test]
+    fn scripthash_deserialize() {
+        // Expectency: A scripthash summary must be parsed into an Address.
+        let stats = r#"{
+            "funded_txo_count": 1,
+            "funded_txo_sum": 1000,
+            "spent_txo_count": 0,
+            "spent_txo_sum": 0,
+            "tx_count": 1
+        }"#;
+        let json = format!(
+            r#"{{"scripthash": "8ba3", "chain_stats": {}, "mempool_stats": {}}}"#,
+            stats, stats
+        );
+        let address: Address = serde_json::from_str(&json).unwrap();
+        assert_eq!(address.address, "8ba3");
+        assert_eq!(address.total_balance(), 2000);
+    }
+
+    #[test]
+    fn utxo_deserialize() {
+        // Expectency: An unspent output must be parsed into an Utxo.
+        let json = r#"{
+            "txid": "12f96289f8f9cd51ccfe390879a46d7eeb0435d9e0af9297776e6bdf249414ff",
+            "vout": 0,
+            "status": {"confirmed": true, "block_height": 698642},
+            "value": 1000
+        }"#;
+        let utxo: Utxo = serde_json::from_str(json).unwrap();
+        assert_eq!(utxo.vout, 0);
+        assert_eq!(utxo.status.block_height, Some(698642));
+        assert_eq!(utxo.value, 1000);
+    }
+}
@@ -0,0 +1,950 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Module containing an asynchronous client for the mempool.space REST API.
+//!
+//! # Notes
+//! Requires crate to be configured with feature "async".
+
+// Imports
+use super::address::{Address, Utxo};
+use super::block::{Block, BlockStatus};
+use super::cache::ResponseCache;
+use super::client::{DEFAULT_REQUEST_TIMEOUT, DEFAULT_USER_AGENT};
+use super::difficulty::DifficultyAdjustment;
+use super::endpoint::{self, check_status, Endpoint};
+use super::error::{MempoolError, ParseTargetError};
+use super::failover::{is_failover_error, Backend, BackendPool};
+use super::fees::{MempoolBlock, RecommendedFees};
+use super::lightning::{
+    Channel, LatestNetworkStats, LightningNode, LightningSearch, NodeRanking, TopNode,
+};
+use super::mempool::{MempoolInfo, RecentTransaction};
+use super::mining::{
+    DifficultyEntry, Hashrate, Interval, MiningPools, PoolDetails, PoolHashrate, RewardStats,
+};
+use super::network::Network;
+use super::prices::{Currency, HistoricalPrice, Prices};
+use super::rate_limit::{RateLimiter, RetryPolicy};
+use super::transaction::{MerkleProof, Outspend, Transaction, TxStatus};
+use super::transport::{
+    default_async_transport, AsyncHttpTransport, HttpMethod, HttpRequest, HttpResponse,
+};
+use futures::stream::{self, Stream, StreamExt};
+use std::sync::Arc;
+use std::time::Duration;
+
+// Documentation imports
+#[cfg(doc)]
+use super::MempoolClient;
+#[cfg(doc)]
+use super::{
+    cache::CachePolicy, error::RejectReason, failover::DEFAULT_CHECK_INTERVAL, target::TcpTarget,
+};
+
+/// Asynchronous client for the mempool.space REST API.
+///
+/// Offers the same endpoints as [MempoolClient] without blocking the executing thread.
+///
+/// # Example
+/// ```no_run
+/// # use mempool_space::AsyncMempoolClient;
+///
+/// # async fn example() {
+/// let client = AsyncMempoolClient::default();
+/// println!("Current tip: {}", client.get_block_tip_height().await.unwrap());
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct AsyncMempoolClient {
+    /// Base url all endpoint paths are appended to, e.g. "https://mempool.space/api".
+    base_url: String,
+    /// [Duration] used as timeout for each request.
+    timeout: Duration,
+    /// User agent sent with each request.
+    user_agent: String,
+    /// [AsyncHttpTransport] performing all requests.
+    transport: Arc<dyn AsyncHttpTransport>,
+    /// Cache consulted before each GET request, if any.
+    cache: Option<Arc<ResponseCache>>,
+    /// Limiter delaying each request, if any.
+    rate_limiter: Option<Arc<RateLimiter>>,
+    /// Policy retrying rate limited requests, if any.
+    retry_policy: Option<RetryPolicy>,
+    /// Backends requests are routed to, if constructed from multiple base urls.
+    backends: Option<Arc<BackendPool>>,
+}
+
+impl AsyncMempoolClient {
+    /// Construct an [AsyncMempoolClient].
+    ///
+    /// # Arguments
+    /// * base_url: url of the API to query, e.g. "https://mempool.space/api".
+    ///
+    /// # Returns
+    /// Instance of [AsyncMempoolClient] using [DEFAULT_REQUEST_TIMEOUT] and [DEFAULT_USER_AGENT].
+    ///
+    /// # Notes
+    /// For more convenience use the implementation of trait "From" for [Network].
+    pub fn new(base_url: &str) -> Self {
+        AsyncMempoolClient {
+            base_url: String::from(base_url.trim_end_matches('/')),
+            timeout: DEFAULT_REQUEST_TIMEOUT,
+            user_agent: String::from(DEFAULT_USER_AGENT),
+            transport: default_async_transport(),
+            cache: None,
+            rate_limiter: None,
+            retry_policy: None,
+            backends: None,
+        }
+    }
+
+    /// Construct an [AsyncMempoolClient] failing over between multiple backends.
+    ///
+    /// # Arguments
+    /// * base_urls: urls of the APIs to query, in order of preference.
+    ///
+    /// # Returns
+    /// * On success, the [AsyncMempoolClient] using [DEFAULT_CHECK_INTERVAL].
+    /// * On failure, a [ParseTargetError] if no base url was given or a base url is malformed.
+    ///
+    /// # Notes
+    /// Behaves like [MempoolClient::from_base_urls]. Checks run as a separate tokio task,
+    /// their blocking [TcpTarget] checks are offloaded onto a separate thread.
+    pub fn from_base_urls(base_urls: &[&str]) -> Result<Self, ParseTargetError> {
+        let backends = BackendPool::new(base_urls)?;
+        let mut client = AsyncMempoolClient::new(base_urls[0]);
+        client.backends = Some(Arc::new(backends));
+        Ok(client)
+    }
+
+    /// Set a new timeout [Duration] applied to each request.
+    pub fn set_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set a new user agent sent with each request.
+    pub fn set_user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = String::from(user_agent);
+        self
+    }
+
+    /// Set a new [AsyncHttpTransport] performing all requests, e.g. a mock transport in tests.
+    pub fn set_transport(mut self, transport: Arc<dyn AsyncHttpTransport>) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Set a [ResponseCache] consulted before each GET request. Responses are stored
+    /// according to the caches [CachePolicy].
+    ///
+    /// # Notes
+    /// A cache may be shared between multiple clients, entries are separated by base url.
+    pub fn set_cache(mut self, cache: Arc<ResponseCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Set a [RateLimiter] each request must take a token from before being sent.
+    ///
+    /// # Notes
+    /// A limiter may be shared between multiple clients to limit their combined request rate.
+    pub fn set_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Set a [RetryPolicy] applied to requests failing with [MempoolError::RateLimited].
+    pub fn set_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Set a new [Duration] between two availability checks of the backends. Only applies
+    /// to clients constructed via [AsyncMempoolClient::from_base_urls].
+    pub fn set_check_interval(mut self, check_interval: Duration) -> Self {
+        if let Some(backends) = self.backends.as_mut().and_then(Arc::get_mut) {
+            backends.set_check_interval(check_interval);
+        }
+        self
+    }
+
+    /// Get a reference to the base url in use.
+    pub fn get_base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Get a reference to the timeout [Duration] in use.
+    pub fn get_timeout(&self) -> &Duration {
+        &self.timeout
+    }
+
+    /// Get a reference to the user agent in use.
+    pub fn get_user_agent(&self) -> &str {
+        &self.user_agent
+    }
+
+    /// Get a reference to the [AsyncHttpTransport] in use.
+    pub fn get_transport(&self) -> &Arc<dyn AsyncHttpTransport> {
+        &self.transport
+    }
+
+    /// Get a reference to the [ResponseCache] in use, if any.
+    pub fn get_cache(&self) -> Option<&Arc<ResponseCache>> {
+        self.cache.as_ref()
+    }
+
+    /// Get a reference to the [RateLimiter] in use, if any.
+    pub fn get_rate_limiter(&self) -> Option<&Arc<RateLimiter>> {
+        self.rate_limiter.as_ref()
+    }
+
+    /// Get a reference to the [RetryPolicy] in use, if any.
+    pub fn get_retry_policy(&self) -> Option<&RetryPolicy> {
+        self.retry_policy.as_ref()
+    }
+
+    /// Get a reference to the [Duration] between two availability checks of the backends,
+    /// if constructed via [AsyncMempoolClient::from_base_urls].
+    pub fn get_check_interval(&self) -> Option<&Duration> {
+        self.backends
+            .as_deref()
+            .map(BackendPool::get_check_interval)
+    }
+
+    /// Get a snapshot of all [Backend]s in order of preference. Empty unless constructed
+    /// via [AsyncMempoolClient::from_base_urls].
+    pub fn get_backends(&self) -> Vec<Backend> {
+        self.backends
+            .as_deref()
+            .map(BackendPool::get_backends)
+            .unwrap_or_default()
+    }
+
+    /// Get the height of the current chain tip via `GET /blocks/tip/height`.
+    pub async fn get_block_tip_height(&self) -> Result<u64, MempoolError> {
+        self.fetch(endpoint::block_tip_height()).await
+    }
+
+    /// Get the hash of the current chain tip via `GET /blocks/tip/hash`.
+    pub async fn get_block_tip_hash(&self) -> Result<String, MempoolError> {
+        self.fetch(endpoint::block_tip_hash()).await
+    }
+
+    /// Get a [Block] by its hash via `GET /block/:hash`.
+    pub async fn get_block(&self, hash: &str) -> Result<Block, MempoolError> {
+        self.fetch(endpoint::block(hash)).await
+    }
+
+    /// Get the hex encoded header of a block via `GET /block/:hash/header`.
+    pub async fn get_block_header(&self, hash: &str) -> Result<String, MempoolError> {
+        self.fetch(endpoint::block_header(hash)).await
+    }
+
+    /// Get the chain [BlockStatus] of a block via `GET /block/:hash/status`.
+    pub async fn get_block_status(&self, hash: &str) -> Result<BlockStatus, MempoolError> {
+        self.fetch(endpoint::block_status(hash)).await
+    }
+
+    /// Get the txids of all transactions of a block via `GET /block/:hash/txids`.
+    pub async fn get_block_txids(&self, hash: &str) -> Result<Vec<String>, MempoolError> {
+        self.fetch(endpoint::block_txids(hash)).await
+    }
+
+    /// Get a page of 25 transactions of a block via `GET /block/:hash/txs/:start_index`.
+    ///
+    /// # Arguments
+    /// * hash: the hash of the block.
+    /// * start_index: index of the first transaction of the page. Must be a multiple of 25.
+    pub async fn get_block_transactions(
+        &self,
+        hash: &str,
+        start_index: u32,
+    ) -> Result<Vec<Transaction>, MempoolError> {
+        self.fetch(endpoint::block_transactions(hash, start_index))
+            .await
+    }
+
+    /// Get a block as raw bytes via `GET /block/:hash/raw`.
+    pub async fn get_block_raw(&self, hash: &str) -> Result<Vec<u8>, MempoolError> {
+        self.fetch(endpoint::block_raw(hash)).await
+    }
+
+    /// Get the hash of the block at given height of the best chain via `GET /block-height/:height`.
+    pub async fn get_block_hash(&self, height: u64) -> Result<String, MempoolError> {
+        self.fetch(endpoint::block_hash(height)).await
+    }
+
+    /// Get the 10 newest blocks via `GET /blocks/:start_height`.
+    ///
+    /// # Arguments
+    /// * start_height: height of the newest block to return, None for the chain tip.
+    pub async fn get_blocks(&self, start_height: Option<u64>) -> Result<Vec<Block>, MempoolError> {
+        self.fetch(endpoint::blocks(start_height)).await
+    }
+
+    /// Get a [Transaction] by its txid via `GET /tx/:txid`.
+    pub async fn get_transaction(&self, txid: &str) -> Result<Transaction, MempoolError> {
+        self.fetch(endpoint::transaction(txid)).await
+    }
+
+    /// Get the confirmation [TxStatus] of a transaction via `GET /tx/:txid/status`.
+    pub async fn get_transaction_status(&self, txid: &str) -> Result<TxStatus, MempoolError> {
+        self.fetch(endpoint::transaction_status(txid)).await
+    }
+
+    /// Get a transaction hex encoded via `GET /tx/:txid/hex`.
+    pub async fn get_transaction_hex(&self, txid: &str) -> Result<String, MempoolError> {
+        self.fetch(endpoint::transaction_hex(txid)).await
+    }
+
+    /// Get a transaction as raw bytes via `GET /tx/:txid/raw`.
+    pub async fn get_transaction_raw(&self, txid: &str) -> Result<Vec<u8>, MempoolError> {
+        self.fetch(endpoint::transaction_raw(txid)).await
+    }
+
+    /// Get the [MerkleProof] of a confirmed transaction via `GET /tx/:txid/merkle-proof`.
+    pub async fn get_transaction_merkle_proof(
+        &self,
+        txid: &str,
+    ) -> Result<MerkleProof, MempoolError> {
+        self.fetch(endpoint::transaction_merkle_proof(txid)).await
+    }
+
+    /// Get the hex encoded merkleblock proof of a confirmed transaction via
+    /// `GET /tx/:txid/merkleblock-proof`.
+    ///
+    /// # Notes
+    /// The proof uses the format of bitcoind's `gettxoutproof`.
+    pub async fn get_transaction_merkleblock_proof(
+        &self,
+        txid: &str,
+    ) -> Result<String, MempoolError> {
+        self.fetch(endpoint::transaction_merkleblock_proof(txid))
+            .await
+    }
+
+    /// Get the [Outspend] of a single transaction output via `GET /tx/:txid/outspend/:vout`.
+    pub async fn get_transaction_outspend(
+        &self,
+        txid: &str,
+        vout: u32,
+    ) -> Result<Outspend, MempoolError> {
+        self.fetch(endpoint::transaction_outspend(txid, vout)).await
+    }
+
+    /// Get the [Outspend] of all transaction outputs via `GET /tx/:txid/outspends`.
+    pub async fn get_transaction_outspends(
+        &self,
+        txid: &str,
+    ) -> Result<Vec<Outspend>, MempoolError> {
+        self.fetch(endpoint::transaction_outspends(txid)).await
+    }
+
+    /// Get an [Address] summary via `GET /address/:address`.
+    pub async fn get_address(&self, address: &str) -> Result<Address, MempoolError> {
+        self.fetch(endpoint::summary(&endpoint::address_path(address)))
+            .await
+    }
+
+    /// Get the newest transactions of an address via `GET /address/:address/txs`.
+    ///
+    /// # Notes
+    /// Returns up to 50 mempool transactions followed by the newest 25 confirmed transactions.
+    pub async fn get_address_transactions(
+        &self,
+        address: &str,
+    ) -> Result<Vec<Transaction>, MempoolError> {
+        self.fetch(endpoint::transactions(&endpoint::address_path(address)))
+            .await
+    }
+
+    /// Get a page of confirmed transactions of an address via
+    /// `GET /address/:address/txs/chain/:last_seen_txid`.
+    ///
+    /// # Arguments
+    /// * address: the address to get the transactions of.
+    /// * last_seen_txid: txid of the last transaction of the previous page, None for the first page.
+    pub async fn get_address_transactions_chain(
+        &self,
+        address: &str,
+        last_seen_txid: Option<&str>,
+    ) -> Result<Vec<Transaction>, MempoolError> {
+        self.fetch(endpoint::transactions_chain(
+            &endpoint::address_path(address),
+            last_seen_txid,
+        ))
+        .await
+    }
+
+    /// Get the unconfirmed transactions of an address via `GET /address/:address/txs/mempool`.
+    pub async fn get_address_transactions_mempool(
+        &self,
+        address: &str,
+    ) -> Result<Vec<Transaction>, MempoolError> {
+        self.fetch(endpoint::transactions_mempool(&endpoint::address_path(
+            address,
+        )))
+        .await
+    }
+
+    /// Get the unspent outputs of an address via `GET /address/:address/utxo`.
+    pub async fn get_address_utxos(&self, address: &str) -> Result<Vec<Utxo>, MempoolError> {
+        self.fetch(endpoint::utxos(&endpoint::address_path(address)))
+            .await
+    }
+
+    /// Get a stream over the full confirmed transaction history of an address, newest first.
+    ///
+    /// # Notes
+    /// Pages are requested lazily via `GET /address/:address/txs/chain/:last_seen_txid`.
+    /// The stream ends after the first empty page or after yielding the first error.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use futures::StreamExt;
+    /// # use mempool_space::AsyncMempoolClient;
+    ///
+    /// # async fn example() {
+    /// let client = AsyncMempoolClient::default();
+    /// let mut history = Box::pin(client.get_address_history("1wiz18xYmhRX6xStj2b9t1rwWX4GKUgpv"));
+    /// while let Some(tx) = history.next().await {
+    ///     println!("{}", tx.unwrap().txid);
+    /// }
+    /// # }
+    /// ```
+    pub fn get_address_history(
+        &self,
+        address: &str,
+    ) -> impl Stream<Item = Result<Transaction, MempoolError>> + '_ {
+        self.history(endpoint::address_path(address))
+    }
+
+    /// Get an [Address] summary of a scripthash via `GET /scripthash/:hash`.
+    ///
+    /// # Notes
+    /// The scripthash is the hex encoded SHA256 hash of the scriptPubKey in reversed byte order.
+    pub async fn get_scripthash(&self, scripthash: &str) -> Result<Address, MempoolError> {
+        self.fetch(endpoint::summary(&endpoint::scripthash_path(scripthash)))
+            .await
+    }
+
+    /// Get the newest transactions of a scripthash via `GET /scripthash/:hash/txs`.
+    pub async fn get_scripthash_transactions(
+        &self,
+        scripthash: &str,
+    ) -> Result<Vec<Transaction>, MempoolError> {
+        self.fetch(endpoint::transactions(&endpoint::scripthash_path(
+            scripthash,
+        )))
+        .await
+    }
+
+    /// Get a page of confirmed transactions of a scripthash via
+    /// `GET /scripthash/:hash/txs/chain/:last_seen_txid`.
+    ///
+    /// # Arguments
+    /// * scripthash: the scripthash to get the transactions of.
+    /// * last_seen_txid: txid of the last transaction of the previous page, None for the first page.
+    pub async fn get_scripthash_transactions_chain(
+        &self,
+        scripthash: &str,
+        last_seen_txid: Option<&str>,
+    ) -> Result<Vec<Transaction>, MempoolError> {
+        self.fetch(endpoint::transactions_chain(
+            &endpoint::scripthash_path(scripthash),
+            last_seen_txid,
+        ))
+        .await
+    }
+
+    /// Get the unconfirmed transactions of a scripthash via `GET /scripthash/:hash/txs/mempool`.
+    pub async fn get_scripthash_transactions_mempool(
+        &self,
+        scripthash: &str,
+    ) -> Result<Vec<Transaction>, MempoolError> {
+        self.fetch(endpoint::transactions_mempool(&endpoint::scripthash_path(
+            scripthash,
+        )))
+        .await
+    }
+
+    /// Get the unspent outputs of a scripthash via `GET /scripthash/:hash/utxo`.
+    pub async fn get_scripthash_utxos(&self, scripthash: &str) -> Result<Vec<Utxo>, MempoolError> {
+        self.fetch(endpoint::utxos(&endpoint::scripthash_path(scripthash)))
+            .await
+    }
+
+    /// Get a stream over the full confirmed transaction history of a scripthash, newest first.
+    ///
+    /// # Notes
+    /// See [get_address_history](AsyncMempoolClient::get_address_history).
+    pub fn get_scripthash_history(
+        &self,
+        scripthash: &str,
+    ) -> impl Stream<Item = Result<Transaction, MempoolError>> + '_ {
+        self.history(endpoint::scripthash_path(scripthash))
+    }
+
+    /// Broadcast a signed transaction via `POST /tx`.
+    ///
+    /// # Arguments
+    /// * raw_hex: the hex encoded signed transaction.
+    ///
+    /// # Returns
+    /// * On success, the txid of the broadcast transaction.
+    /// * On rejection by the node, [MempoolError::Rejected] with the classified [RejectReason].
+    /// * On any other failure, the corresponding [MempoolError].
+    pub async fn broadcast(&self, raw_hex: &str) -> Result<String, MempoolError> {
+        self.fetch(endpoint::broadcast(raw_hex))
+            .await
+            .map_err(endpoint::broadcast_error)
+    }
+
+    /// Get the current mempool backlog statistics via `GET /mempool`.
+    pub async fn get_mempool(&self) -> Result<MempoolInfo, MempoolError> {
+        self.fetch(endpoint::mempool()).await
+    }
+
+    /// Get the txids of all mempool transactions via `GET /mempool/txids`.
+    pub async fn get_mempool_txids(&self) -> Result<Vec<String>, MempoolError> {
+        self.fetch(endpoint::mempool_txids()).await
+    }
+
+    /// Get the last 10 transactions added to the mempool via `GET /mempool/recent`.
+    pub async fn get_mempool_recent(&self) -> Result<Vec<RecentTransaction>, MempoolError> {
+        self.fetch(endpoint::mempool_recent()).await
+    }
+
+    /// Get the currently recommended feerates via `GET /v1/fees/recommended`.
+    pub async fn get_recommended_fees(&self) -> Result<RecommendedFees, MempoolError> {
+        self.fetch(endpoint::recommended_fees()).await
+    }
+
+    /// Get the blocks projected from the current mempool via `GET /v1/fees/mempool-blocks`.
+    ///
+    /// # Notes
+    /// Use [feerate_for_target](crate::fees::feerate_for_target) to pick a feerate for a
+    /// confirmation target from the returned blocks.
+    pub async fn get_mempool_blocks(&self) -> Result<Vec<MempoolBlock>, MempoolError> {
+        self.fetch(endpoint::mempool_blocks()).await
+    }
+
+    /// Get the progress of the current difficulty epoch via `GET /v1/difficulty-adjustment`.
+    pub async fn get_difficulty_adjustment(&self) -> Result<DifficultyAdjustment, MempoolError> {
+        self.fetch(endpoint::difficulty_adjustment()).await
+    }
+
+    /// Get the block counts of all mining pools within given [Interval] via
+    /// `GET /v1/mining/pools/:interval`.
+    pub async fn get_mining_pools(&self, interval: Interval) -> Result<MiningPools, MempoolError> {
+        self.fetch(endpoint::mining_pools(interval)).await
+    }
+
+    /// Get the [PoolDetails] of a mining pool via `GET /v1/mining/pool/:slug`.
+    ///
+    /// # Arguments
+    /// * slug: the slug of the mining pool as listed by [PoolStats](crate::mining::PoolStats), e.g. "foundryusa".
+    pub async fn get_mining_pool(&self, slug: &str) -> Result<PoolDetails, MempoolError> {
+        self.fetch(endpoint::mining_pool(slug)).await
+    }
+
+    /// Get the hashrate history of a mining pool via `GET /v1/mining/pool/:slug/hashrate`.
+    pub async fn get_mining_pool_hashrate(
+        &self,
+        slug: &str,
+    ) -> Result<Vec<PoolHashrate>, MempoolError> {
+        self.fetch(endpoint::mining_pool_hashrate(slug)).await
+    }
+
+    /// Get the 10 newest blocks mined by a mining pool via `GET /v1/mining/pool/:slug/blocks/:height`.
+    ///
+    /// # Arguments
+    /// * slug: the slug of the mining pool.
+    /// * before_height: only return blocks below this height, None for the newest blocks.
+    pub async fn get_mining_pool_blocks(
+        &self,
+        slug: &str,
+        before_height: Option<u64>,
+    ) -> Result<Vec<Block>, MempoolError> {
+        self.fetch(endpoint::mining_pool_blocks(slug, before_height))
+            .await
+    }
+
+    /// Get the network [Hashrate] and difficulty within given [Interval] via
+    /// `GET /v1/mining/hashrate/:interval`.
+    pub async fn get_hashrate(&self, interval: Interval) -> Result<Hashrate, MempoolError> {
+        self.fetch(endpoint::hashrate(interval)).await
+    }
+
+    /// Get past difficulty adjustments, newest first, via
+    /// `GET /v1/mining/difficulty-adjustments/:interval`.
+    ///
+    /// # Arguments
+    /// * interval: the [Interval] to get the adjustments of, None for all adjustments.
+    pub async fn get_difficulty_adjustments(
+        &self,
+        interval: Option<Interval>,
+    ) -> Result<Vec<DifficultyEntry>, MempoolError> {
+        self.fetch(endpoint::difficulty_adjustments(interval)).await
+    }
+
+    /// Get the [RewardStats] of the given number of newest blocks via
+    /// `GET /v1/mining/reward-stats/:blockCount`.
+    pub async fn get_reward_stats(&self, block_count: u32) -> Result<RewardStats, MempoolError> {
+        self.fetch(endpoint::reward_stats(block_count)).await
+    }
+
+    /// Get the latest lightning [NetworkStats] via `GET /v1/lightning/statistics/latest`.
+    pub async fn get_lightning_statistics(&self) -> Result<LatestNetworkStats, MempoolError> {
+        self.fetch(endpoint::lightning_statistics()).await
+    }
+
+    /// Search lightning nodes by alias or public key and channels by short channel id via
+    /// `GET /v1/lightning/search?searchText=:query`.
+    pub async fn search_lightning(&self, query: &str) -> Result<LightningSearch, MempoolError> {
+        self.fetch(endpoint::search_lightning(query)).await
+    }
+
+    /// Get a [LightningNode] by its public key via `GET /v1/lightning/nodes/:public_key`.
+    pub async fn get_lightning_node(
+        &self,
+        public_key: &str,
+    ) -> Result<LightningNode, MempoolError> {
+        self.fetch(endpoint::lightning_node(public_key)).await
+    }
+
+    /// Get a lightning [Channel] by its id via `GET /v1/lightning/channels/:short_id`.
+    ///
+    /// # Arguments
+    /// * short_id: the short channel id, e.g. "698925x2156x0", or the channel id as integer.
+    pub async fn get_lightning_channel(&self, short_id: &str) -> Result<Channel, MempoolError> {
+        self.fetch(endpoint::lightning_channel(short_id)).await
+    }
+
+    /// Get the top 100 lightning nodes of given [NodeRanking] via
+    /// `GET /v1/lightning/nodes/rankings/:ranking`.
+    pub async fn get_lightning_top_nodes(
+        &self,
+        ranking: NodeRanking,
+    ) -> Result<Vec<TopNode>, MempoolError> {
+        self.fetch(endpoint::lightning_top_nodes(ranking)).await
+    }
+
+    /// Get the current bitcoin [Prices] via `GET /v1/prices`.
+    pub async fn get_prices(&self) -> Result<Prices, MempoolError> {
+        self.fetch(endpoint::prices()).await
+    }
+
+    /// Get the bitcoin price closest to given timestamp via `GET /v1/historical-price`.
+    ///
+    /// # Arguments
+    /// * currency: the [Currency] to get the price in.
+    /// * timestamp: point in time in seconds since the unix epoch, e.g. a blocks timestamp.
+    pub async fn get_historical_price(
+        &self,
+        currency: Currency,
+        timestamp: u64,
+    ) -> Result<HistoricalPrice, MempoolError> {
+        self.fetch(endpoint::historical_price(currency, timestamp))
+            .await
+    }
+
+    /// Get a stream over the confirmed transactions of given address or scripthash path,
+    /// requesting one page of the `txs/chain/:last_seen_txid` pagination at a time.
+    fn history(&self, path: String) -> impl Stream<Item = Result<Transaction, MempoolError>> + '_ {
+        // State: None once exhausted, otherwise the txid of the last transaction seen, if any
+        let pages = stream::unfold(Some(None), move |last_seen_txid: Option<Option<String>>| {
+            let endpoint =
+                endpoint::transactions_chain(&path, last_seen_txid.clone().flatten().as_deref());
+            async move {
+                last_seen_txid.as_ref()?;
+                match self.fetch(endpoint).await {
+                    Ok(page) if page.is_empty() => None,
+                    Ok(page) => {
+                        let last_seen_txid = page.last().map(|tx| tx.txid.clone());
+                        let page: Vec<_> = page.into_iter().map(Ok).collect();
+                        Some((page, Some(last_seen_txid)))
+                    }
+                    Err(error) => Some((vec![Err(error)], None)),
+                }
+            }
+        });
+        pages.flat_map(stream::iter)
+    }
+
+    /// Request given [Endpoint] and decode its response body. Responses of GET requests are
+    /// served from and stored into the [ResponseCache] if set.
+    async fn fetch<T>(&self, endpoint: Endpoint<T>) -> Result<T, MempoolError> {
+        let cache = match endpoint.get_method() {
+            HttpMethod::Get => self.cache.as_ref(),
+            _ => None,
+        };
+        let path = endpoint.get_path();
+        if let Some(cache) = cache {
+            if let Some(body) = self.cache_get(cache, path).await {
+                return endpoint.decode(body);
+            }
+        }
+        let response = self
+            .route(|base_url| endpoint.request(base_url, self.timeout, &self.user_agent))
+            .await?;
+        let body = response.into_body();
+        if let Some(cache) = cache {
+            self.cache_insert(cache, path, &body).await;
+        }
+        endpoint.decode(body)
+    }
+
+    /// Look up given endpoint path in the [ResponseCache]. Caches backed by a directory are
+    /// read on the blocking thread pool, keeping file access off the async workers.
+    async fn cache_get(&self, cache: &Arc<ResponseCache>, path: &str) -> Option<Vec<u8>> {
+        if cache.get_directory().is_none() {
+            return cache.get(&self.base_url, path);
+        }
+        let (cache, base_url, path) = (cache.clone(), self.base_url.clone(), path.to_string());
+        tokio::task::spawn_blocking(move || cache.get(&base_url, &path))
+            .await
+            .ok()
+            .flatten()
+    }
+
+    /// Store given response body of given endpoint path in the [ResponseCache]. Caches backed
+    /// by a directory are written on the blocking thread pool.
+    async fn cache_insert(&self, cache: &Arc<ResponseCache>, path: &str, body: &[u8]) {
+        if cache.get_directory().is_none() {
+            return cache.insert(&self.base_url, path, body);
+        }
+        let (cache, base_url, path) = (cache.clone(), self.base_url.clone(), path.to_string());
+        let body = body.to_vec();
+        let _ = tokio::task::spawn_blocking(move || cache.insert(&base_url, &path, &body)).await;
+    }
+
+    /// Send the request built for a base url to the first backend answering it. Without
+    /// backends, the request is sent to the base url.
+    async fn route(
+        &self,
+        request: impl Fn(&str) -> HttpRequest,
+    ) -> Result<HttpResponse, MempoolError> {
+        let backends = match &self.backends {
+            Some(backends) => backends,
+            None => return self.send(&request(&self.base_url)).await,
+        };
+        if backends.claim_check() {
+            // Check in the background, meanwhile requests are routed by the previous results
+            let (backends, transport) = (backends.clone(), self.transport.clone());
+            let (timeout, user_agent) = (self.timeout, self.user_agent.clone());
+            tokio::spawn(async move {
+                backends
+                    .check_async(transport.as_ref(), timeout, &user_agent)
+                    .await
+            });
+        }
+
+        let mut last_error = None;
+        for (index, base_url) in backends.route() {
+            match self.send(&request(&base_url)).await {
+                Err(error) if is_failover_error(&error) => {
+                    backends.mark_failed(index);
+                    last_error = Some(error);
+                }
+                result => return result,
+            }
+        }
+        Err(last_error.expect("BackendPool contains at least one backend"))
+    }
+
+    /// Send given request once the [RateLimiter] permits, failing on non-success status codes.
+    /// Rate limited requests are retried according to the [RetryPolicy].
+    async fn send(&self, request: &HttpRequest) -> Result<HttpResponse, MempoolError> {
+        let mut retry = 0;
+        loop {
+            if let Some(rate_limiter) = &self.rate_limiter {
+                tokio::time::sleep(rate_limiter.reserve()).await;
+            }
+            let retry_after = match check_status(self.transport.send(request).await?) {
+                Err(MempoolError::RateLimited { retry_after }) => retry_after,
+                result => return result,
+            };
+            match self
+                .retry_policy
+                .as_ref()
+                .and_then(|policy| policy.get_delay(retry, retry_after))
+            {
+                Some(delay) => tokio::time::sleep(delay).await,
+                None => return Err(MempoolError::RateLimited { retry_after }),
+            }
+            retry += 1;
+        }
+    }
+}
+
+impl From<Network> for AsyncMempoolClient {
+    fn from(network: Network) -> Self {
+        AsyncMempoolClient::new(network.base_url())
+    }
+}
+
+impl Default for AsyncMempoolClient {
+    fn default() -> Self {
+        AsyncMempoolClient::from(Network::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::RejectReason;
+    use crate::target::Status;
+    use crate::test_utils::{response, serve, transaction_page};
+    use crate::transport::MockAsyncHttpTransport;
+    use futures::FutureExt;
+
+    #[test]
+    fn async_mempool_client_new() {
+        // Expectency: The async client must be configurable like the blocking client.
+        let client = AsyncMempoolClient::from(Network::Testnet4)
+            .set_timeout(Duration::from_secs(1))
+            .set_user_agent("test-agent");
+        assert_eq!(client.get_base_url(), "https://mempool.space/testnet4/api");
+        assert_eq!(client.get_timeout(), &Duration::from_secs(1));
+        assert_eq!(client.get_user_agent(), "test-agent");
+    }
+
+    #[tokio::test]
+    async fn async_mempool_client_get_block_tip_height() {
+        // Expectency: The tip height must be requested from the configured base url.
+        let (base_url, srv) = serve(vec![response(200, "840000")]);
+        let client = AsyncMempoolClient::new(&format!("{}/api", base_url));
+        assert_eq!(client.get_block_tip_height().await.unwrap(), 840000);
+
+        let requests = srv.join().unwrap();
+        assert!(requests[0].starts_with("GET /api/blocks/tip/height HTTP/1.1"));
+    }
+
+    #[tokio::test]
+    async fn async_mempool_client_get_mempool() {
+        // Expectency: JSON responses must be deserialized into their typed counterparts.
+        let body = r#"{"count":1,"vsize":141,"total_fee":282,"fee_histogram":[[2.0,141]]}"#;
+        let (base_url, srv) = serve(vec![response(200, body)]);
+        let mempool = AsyncMempoolClient::new(&base_url)
+            .get_mempool()
+            .await
+            .unwrap();
+        assert_eq!(mempool.count, 1);
+        assert_eq!(mempool.fee_histogram, vec![(2.0, 141)]);
+        srv.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn async_mempool_client_retry() {
+        // Expectency: Rate limited requests are retried, rate limited by the limiter.
+        let (base_url, srv) = serve(vec![response(503, ""), response(200, "840000")]);
+        let client = AsyncMempoolClient::new(&base_url)
+            .set_rate_limiter(Arc::new(RateLimiter::new(1000.0, 1).unwrap()))
+            .set_retry_policy(RetryPolicy::new(1).set_base_delay(Duration::from_millis(10)));
+        assert_eq!(client.get_block_tip_height().await.unwrap(), 840000);
+        assert_eq!(srv.join().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn async_mempool_client_failover() {
+        // Expectency: Unreachable backends are skipped in favour of available ones, the
+        //             availability check runs in the background.
+        let unreachable = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}", listener.local_addr().unwrap())
+        };
+        let (base_url, srv) = serve(vec![response(200, "840000"), response(200, "840000")]);
+        let client = AsyncMempoolClient::from_base_urls(&[&unreachable, &base_url])
+            .unwrap()
+            .set_check_interval(Duration::from_secs(60));
+        assert_eq!(client.get_check_interval(), Some(&Duration::from_secs(60)));
+        assert_eq!(client.get_block_tip_height().await.unwrap(), 840000);
+
+        // Wait for the availability check claimed by the request
+        while client
+            .get_backends()
+            .iter()
+            .any(|backend| backend.get_status() == &Status::Unknown)
+        {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let backends = client.get_backends();
+        assert_eq!(backends[0].get_status(), &Status::NotAvailable);
+        assert_eq!(backends[1].get_status(), &Status::Available);
+        assert_eq!(srv.join().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn async_mempool_client_broadcast_rejected() {
+        // Expectency: Rejections of the node must be classified.
+        let (base_url, srv) = serve(vec![response(400, "bad-txns-inputs-missingorspent")]);
+        let error = AsyncMempoolClient::new(&base_url)
+            .broadcast("0100")
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            MempoolError::Rejected(RejectReason::InputsMissingOrSpent, _)
+        ));
+        let requests = srv.join().unwrap();
+        assert!(requests[0].starts_with("POST /tx HTTP/1.1"));
+    }
+
+    #[tokio::test]
+    async fn async_mempool_client_address_history() {
+        // Expectency: The history stream must walk all pages using the last seen txid as cursor.
+        let (base_url, srv) = serve(vec![
+            response(200, &transaction_page(&["a1", "a2"])),
+            response(200, &transaction_page(&["a3"])),
+            response(200, "[]"),
+        ]);
+        let client = AsyncMempoolClient::new(&base_url);
+        let txids: Vec<String> = client
+            .get_address_history("bc1q")
+            .map(|tx| tx.unwrap().txid)
+            .collect()
+            .await;
+        assert_eq!(txids, vec!["a1", "a2", "a3"]);
+
+        let requests = srv.join().unwrap();
+        assert!(requests[1].starts_with("GET /address/bc1q/txs/chain/a2 HTTP/1.1"));
+    }
+
+    #[tokio::test]
+    async fn async_mempool_client_transport() {
+        // Expectency: All requests are performed by the injected transport.
+        let mut transport = MockAsyncHttpTransport::new();
+        transport
+            .expect_send()
+            .withf(|request| {
+                request.get_method() == HttpMethod::Get
+                    && request.get_url() == "http://mock/api/blocks/tip/height"
+                    && request.get_headers()[0]
+                        == (String::from("User-Agent"), String::from("mock"))
+                    && request.get_timeout() == Some(&Duration::from_secs(1))
+            })
+            .times(1)
+            .returning(|_| async { Ok(HttpResponse::new(200, b"840000".to_vec())) }.boxed());
+        let client = AsyncMempoolClient::new("http://mock/api")
+            .set_user_agent("mock")
+            .set_timeout(Duration::from_secs(1))
+            .set_transport(Arc::new(transport));
+        assert_eq!(client.get_block_tip_height().await.unwrap(), 840000);
+    }
+
+    #[tokio::test]
+    async fn async_mempool_client_http_error_status() {
+        // Expectency: Non-success status codes must be reported as error.
+        let (base_url, srv) = serve(vec![response(400, "Invalid hex string")]);
+        let error = AsyncMempoolClient::new(&base_url)
+            .get_block("zz")
+            .await
+            .unwrap_err();
+        assert_eq!(format!("{}", error), "HTTP status 400: Invalid hex string");
+        srv.join().unwrap();
+    }
+}
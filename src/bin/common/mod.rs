@@ -0,0 +1,78 @@
+//! Code shared by the binaries of this crate.
+
+use std::str::FromStr;
+use std::time::{Instant, SystemTime};
+
+use mempool_space::blockheight::weeble_millis_with;
+use mempool_space::{AsyncMempoolClient, Currency, MempoolClient, Network};
+
+/// Fetch prices and tip height of the network given as first argument, blocking and async.
+pub fn run() {
+    // Select the network by name or base url, e.g. "signet" or "http://192.168.1.10:8999/api"
+    let network = std::env::args()
+        .nth(1)
+        .map(|arg| Network::from_str(&arg).expect("invalid network"))
+        .unwrap_or_default();
+    let n = 1;
+    {
+        let start = Instant::now();
+        let res = blocking(n, &network);
+        println!("blocking {:?} {} prices", start.elapsed(), res);
+    }
+    {
+        let start = Instant::now();
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let res = rt.block_on(non_blocking(n, &network));
+        println!("async    {:?} {} prices", start.elapsed(), res);
+    }
+}
+
+fn blocking(n: usize, network: &Network) -> usize {
+    (0..n)
+        .map(|_| {
+            let client = MempoolClient::from(network.clone());
+            std::thread::spawn(move || {
+                // print prices from mempool.space or panic
+                let prices = client.get_prices().expect("failed to fetch prices");
+                Currency::ALL
+                    .into_iter()
+                    .filter_map(|currency| prices.get(currency).map(|price| (currency, price)))
+                    .map(|(currency, price)| println!("{} {}", currency, price))
+                    .count()
+            })
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+        .map(|it| it.join().unwrap())
+        .sum()
+}
+
+async fn non_blocking(n: usize, network: &Network) -> usize {
+    let tasks = (0..n)
+        .map(|_| {
+            let network = network.clone();
+            tokio::spawn(async move {
+                // Query the tip height without blocking the runtime's worker threads
+                let client = AsyncMempoolClient::from(network);
+                let blockheight = client.get_block_tip_height().await;
+                match weeble_millis_with(SystemTime::now, || blockheight) {
+                    Ok(weeble) => println!("weeble {}", weeble),
+                    Err(error) => println!("weeble unavailable: {}", error),
+                }
+
+                // count prices from mempool.space or panic
+                let prices = client.get_prices().await.expect("failed to fetch prices");
+                Currency::ALL
+                    .into_iter()
+                    .filter(|currency| prices.get(*currency).is_some())
+                    .count()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut res = 0;
+    for task in tasks {
+        res += task.await.unwrap();
+    }
+    res
+}
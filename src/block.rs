@@ -0,0 +1,99 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Module containing types returned by the mempool.space block endpoints.
+
+// Imports
+use serde::Deserialize;
+
+/// A block as returned by `GET /block/:hash`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Block {
+    /// Block hash
+    pub id: String,
+    /// Height of the block within the chain
+    pub height: u64,
+    /// Block version
+    pub version: u32,
+    /// Block timestamp in seconds since the unix epoch
+    pub timestamp: u64,
+    /// Number of transactions contained in the block
+    pub tx_count: u64,
+    /// Serialized size in bytes
+    pub size: u64,
+    /// Block weight in weight units
+    pub weight: u64,
+    /// Merkle root of all transactions
+    pub merkle_root: String,
+    /// Hash of the previous block. Missing for the genesis block.
+    pub previousblockhash: Option<String>,
+    /// Median time past of the block
+    pub mediantime: u64,
+    /// Block nonce
+    pub nonce: u64,
+    /// Compact difficulty target
+    pub bits: u32,
+    /// Difficulty of the block
+    pub difficulty: f64,
+}
+
+/// Chain status of a [Block] as returned by `GET /block/:hash/status`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct BlockStatus {
+    /// True if the block is part of the best chain
+    pub in_best_chain: bool,
+    /// Height of the block. Missing for stale blocks.
+    pub height: Option<u64>,
+    /// Hash of the next block in the best chain, if any
+    pub next_best: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_deserialize() {
+        // Expectency: A block response of the esplora API must be parsed into a Block.
+        let json = r#"{
+            "id": "000000000000000015dc777b3ff2611091336355d3f0ee9766a2cf3be8e4b1ce",
+            "height": 363366,
+            "version": 2,
+            "timestamp": 1435766771,
+            "tx_count": 494,
+            "size": 286494,
+            "weight": 1145976,
+            "merkle_root": "9d3cb87bf05ebae366b4262ed5f768ce8c62fc385c3886c9cb097647b04b686c",
+            "previousblockhash": "000000000000000010c545b6fa3ef1f7cf45a2a8760b1ee9f2e89673218207ce",
+            "mediantime": 1435763435,
+            "nonce": 2892644888,
+            "bits": 404111758,
+            "difficulty": 49402014931.22746
+        }"#;
+        let block: Block = serde_json::from_str(json).unwrap();
+        assert_eq!(block.height, 363366);
+        assert_eq!(block.tx_count, 494);
+        assert_eq!(
+            block.previousblockhash.as_deref(),
+            Some("000000000000000010c545b6fa3ef1f7cf45a2a8760b1ee9f2e89673218207ce")
+        );
+    }
+
+    #[test]
+    fn block_status_deserialize() {
+        // Expectency: The fields of a stale block status are missing.
+        let json = r#"{
+            "in_best_chain": true,
+            "height": 690557,
+            "next_best": "00000000000000000001b5f3bf0a0c8a3e4c0a4f6b8a6b9a5f0e9d0b1c2d3e4f"
+        }"#;
+        let status: BlockStatus = serde_json::from_str(json).unwrap();
+        assert!(status.in_best_chain);
+        assert_eq!(status.height, Some(690557));
+
+        let status: BlockStatus = serde_json::from_str(r#"{"in_best_chain": false}"#).unwrap();
+        assert_eq!(status.height, None);
+        assert_eq!(status.next_best, None);
+    }
+}
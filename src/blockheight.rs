@@ -1,35 +1,172 @@
-use std::io::Read;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
-use reqwest::Url;
+use crate::{MempoolClient, MempoolError, Network};
 
 pub fn check_curl() {
 
     // println!("check_curl");
 }
 
-pub fn blockheight() -> Result<f64, ascii::AsciiChar> {
-    let since_the_epoch = SystemTime::now()
+pub fn blockheight() -> Result<u64, MempoolError> {
+    blockheight_on(&Network::Mainnet)
+}
+
+pub fn blockheight_on(network: &Network) -> Result<u64, MempoolError> {
+    MempoolClient::from(network.clone()).get_block_tip_height()
+}
+
+/// Get the gnostr "weeble": seconds since the unix epoch divided by the mainnet tip height.
+pub fn weeble() -> Result<u64, MempoolError> {
+    weeble_with(SystemTime::now, blockheight)
+}
+
+/// Get the gnostr "wobble": seconds since the unix epoch modulo the mainnet tip height.
+pub fn wobble() -> Result<u64, MempoolError> {
+    wobble_with(SystemTime::now, blockheight)
+}
+
+/// Get the gnostr "weeble" in milliseconds: milliseconds since the unix epoch divided by
+/// the mainnet tip height.
+pub fn weeble_millis() -> Result<u64, MempoolError> {
+    weeble_millis_with(SystemTime::now, blockheight)
+}
+
+/// Calculate the weeble from the given clock and height source.
+///
+/// # Arguments
+/// * clock: function returning the current time, e.g. [SystemTime::now].
+/// * height: function returning the current tip height, e.g. [blockheight].
+///
+/// # Returns
+/// * On success, the whole seconds since the unix epoch divided by the tip height, rounded down.
+/// * On failure, the [MempoolError] of the height source.
+///
+/// # Notes
+/// Times before the unix epoch count as 0 seconds and a tip height of 0 is treated as 1,
+/// so the result is always defined.
+///
+/// # Example
+/// ```
+/// # use std::time::{Duration, SystemTime};
+/// # use mempool_space::blockheight::weeble_with;
+///
+/// let now = || SystemTime::UNIX_EPOCH + Duration::from_secs(1_713_571_767);
+/// assert_eq!(weeble_with(now, || Ok(840_000)).unwrap(), 2039);
+/// ```
+pub fn weeble_with<C, H>(clock: C, height: H) -> Result<u64, MempoolError>
+where
+    C: FnOnce() -> SystemTime,
+    H: FnOnce() -> Result<u64, MempoolError>,
+{
+    Ok(since_epoch(clock).as_secs() / height()?.max(1))
+}
+
+/// Calculate the wobble from the given clock and height source.
+///
+/// # Returns
+/// * On success, the whole seconds since the unix epoch modulo the tip height.
+/// * On failure, the [MempoolError] of the height source.
+///
+/// # Notes
+/// Arguments and edge cases are handled like in [weeble_with].
+pub fn wobble_with<C, H>(clock: C, height: H) -> Result<u64, MempoolError>
+where
+    C: FnOnce() -> SystemTime,
+    H: FnOnce() -> Result<u64, MempoolError>,
+{
+    Ok(since_epoch(clock).as_secs() % height()?.max(1))
+}
+
+/// Calculate the weeble in milliseconds from the given clock and height source.
+///
+/// # Returns
+/// * On success, the milliseconds since the unix epoch divided by the tip height, rounded down.
+/// * On failure, the [MempoolError] of the height source.
+///
+/// # Notes
+/// Arguments and edge cases are handled like in [weeble_with].
+pub fn weeble_millis_with<C, H>(clock: C, height: H) -> Result<u64, MempoolError>
+where
+    C: FnOnce() -> SystemTime,
+    H: FnOnce() -> Result<u64, MempoolError>,
+{
+    let millis = since_epoch(clock).as_millis() as u64;
+    Ok(millis / height()?.max(1))
+}
+
+/// Get the [Duration] since the unix epoch of the time returned by given clock.
+fn since_epoch<C: FnOnce() -> SystemTime>(clock: C) -> Duration {
+    clock()
         .duration_since(SystemTime::UNIX_EPOCH)
-        .expect("get millis error");
-    let seconds = since_the_epoch.as_secs();
-    let subsec_millis = since_the_epoch.subsec_millis() as u64;
-    let _now_millis = seconds * 1000 + subsec_millis;
-    // println!("now millis: {}", seconds * 1000 + subsec_millis);
-
-    // let bh = get_blockheight();
-    // println!("{}",bh.unwrap());
-    let url = Url::parse("https://mempool.space/api/blocks/tip/height").unwrap();
-    let mut res = reqwest::blocking::get(url).unwrap();
-
-    let mut tmp_string = String::new();
-    res.read_to_string(&mut tmp_string).unwrap();
-    let tmp_u64 = tmp_string.parse::<u64>().unwrap_or(0);
-
-    // TODO:impl gnostr-weeble_millis
-    // let weeble = now_millis as f64 / tmp_u64 as f64;
-    // let blockheight = seconds as f64 / tmp_u64 as f64;
-    let blockheight = tmp_u64 as f64;
-    // return Ok(blockheight.floor());
-    return Ok(blockheight);
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{closed_addr, response, serve};
+
+    fn clock() -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_millis(1_713_571_767_250)
+    }
+
+    #[test]
+    fn blockheight_on_custom_network() {
+        // Expectency: The tip height of the given network must be returned.
+        let (base_url, srv) = serve(vec![response(200, "840000")]);
+        let blockheight = blockheight_on(&Network::Custom(base_url)).unwrap();
+        assert_eq!(blockheight, 840000);
+        srv.join().unwrap();
+    }
+
+    #[test]
+    fn blockheight_on_non_numeric_body() {
+        // Expectency: A non-numeric response must be reported as error instead of as height 0.
+        let (base_url, srv) = serve(vec![response(200, "Service Unavailable")]);
+        let error = blockheight_on(&Network::Custom(base_url)).unwrap_err();
+        assert!(matches!(error, MempoolError::Decode(_, _)));
+        srv.join().unwrap();
+    }
+
+    #[test]
+    fn blockheight_on_unreachable_network() {
+        // Expectency: A connection failure must be reported as transport error without panicking.
+        let network = Network::Custom(format!("http://{}", closed_addr()));
+        let error = blockheight_on(&network).unwrap_err();
+        assert!(matches!(error, MempoolError::Transport(_, _)));
+    }
+
+    #[test]
+    fn weeble_wobble_with() {
+        // Expectency: weeble and wobble are quotient and remainder of the seconds since
+        //             the unix epoch and the tip height.
+        let height = 840_000;
+        let weeble = weeble_with(clock, || Ok(height)).unwrap();
+        let wobble = wobble_with(clock, || Ok(height)).unwrap();
+        assert_eq!(weeble, 2039);
+        assert_eq!(wobble, 811_767);
+        assert_eq!(weeble * height + wobble, 1_713_571_767);
+        assert_eq!(weeble_millis_with(clock, || Ok(height)).unwrap(), 2_039_966);
+    }
+
+    #[test]
+    fn weeble_wobble_with_edge_cases() {
+        // Expectency: A tip height of 0 is treated as 1 and times before the unix epoch
+        //             count as 0 seconds.
+        assert_eq!(weeble_with(clock, || Ok(0)).unwrap(), 1_713_571_767);
+        assert_eq!(wobble_with(clock, || Ok(0)).unwrap(), 0);
+        let before_epoch = || SystemTime::UNIX_EPOCH - Duration::from_secs(1);
+        assert_eq!(weeble_with(before_epoch, || Ok(840_000)).unwrap(), 0);
+        assert_eq!(weeble_millis_with(before_epoch, || Ok(840_000)).unwrap(), 0);
+    }
+
+    #[test]
+    fn weeble_with_height_error() {
+        // Expectency: Errors of the height source must be passed through.
+        let height = || Err(MempoolError::HttpStatus(503, String::new()));
+        assert_eq!(
+            format!("{}", weeble_with(clock, height).unwrap_err()),
+            "HTTP status 503"
+        );
+    }
 }
@@ -0,0 +1,803 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Module containing a response cache for the mempool.space clients.
+
+// Imports
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Documentation imports
+#[cfg(all(doc, feature = "async"))]
+use super::AsyncMempoolClient;
+#[cfg(doc)]
+use super::MempoolClient;
+
+/// Default number of responses kept in memory by a [ResponseCache]
+pub const DEFAULT_CACHE_CAPACITY: usize = 1024;
+
+/// Default number of responses kept in the on-disk store of a [ResponseCache]
+pub const DEFAULT_DISK_CAPACITY: usize = 16 * 1024;
+
+/// Number of writes into the on-disk store between two prunings
+const DISK_PRUNE_INTERVAL: u64 = 64;
+
+/// Age after which temporary files of interrupted writes are removed
+const STALE_TEMP_FILE_AGE: Duration = Duration::from_secs(60);
+
+/// Extension of entry files within the on-disk store
+const ENTRY_EXTENSION: &str = "entry";
+
+/// Extension of temporary files within the on-disk store
+const TEMP_EXTENSION: &str = "tmp";
+
+/// Counter making the names of temporary files unique within the process
+static TEMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Policy deciding if and how long a response is cached.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum CachePolicy {
+    /// Responses are never cached
+    NoStore,
+    /// Responses are cached for the given [Duration]
+    Ttl(Duration),
+    /// Responses never change and are cached until evicted
+    Immutable,
+}
+
+/// Cache of successful responses shared by [MempoolClient]s, keyed by request url.
+///
+/// Responses are kept in an in-memory LRU of limited capacity and, if a directory is set,
+/// in an on-disk store surviving restarts. Which responses are cached is decided by the
+/// [CachePolicy] of the first pattern matching the endpoint path.
+///
+/// # Notes
+/// Patterns consist of path segments, where ":name" matches any single segment and a
+/// trailing "*" matches all remaining segments, e.g. "/block/:hash" or "/v1/fees/*".
+/// Failing to read or write the on-disk store is not reported, the request is performed
+/// instead.
+///
+/// Entries are written atomically into files named `<hash>.entry`, the directory may be
+/// shared with other files. Expired and incomplete entries are removed when read and by a
+/// periodic pruning on a background thread, which also removes the oldest entries beyond the
+/// disk capacity. Disk access blocks the calling thread, the [AsyncMempoolClient] offloads it
+/// onto a separate thread.
+///
+/// Default policies:
+/// * Immutable: "/block/:hash" including header, txids, txs and raw, "/tx/:txid/hex" and
+///   "/tx/:txid/raw".
+/// * 10 minutes: merkle proofs of transactions.
+/// * 1 minute: "/block/:hash/status" and "/v1/prices".
+/// * 10 seconds: "/blocks/tip/*", "/v1/fees/*", "/mempool" and "/mempool/*".
+/// * Everything else is not cached.
+///
+/// # Example
+/// ```
+/// # use std::{sync::Arc, time::Duration};
+/// # use mempool_space::{CachePolicy, MempoolClient, ResponseCache};
+///
+/// let cache = ResponseCache::new(256)
+///     .set_policy("/v1/difficulty-adjustment", CachePolicy::Ttl(Duration::from_secs(60)));
+/// let client = MempoolClient::default().set_cache(Arc::new(cache));
+/// ```
+#[derive(Debug)]
+pub struct ResponseCache {
+    /// Maximum number of responses kept in memory.
+    capacity: usize,
+    /// Directory of the on-disk store, if any.
+    directory: Option<PathBuf>,
+    /// Maximum number of responses kept in the on-disk store.
+    disk_capacity: usize,
+    /// Patterns and their policies, first match wins.
+    policies: Vec<(String, CachePolicy)>,
+    /// In-memory LRU.
+    memory: Mutex<Memory>,
+    /// Number of writes into the on-disk store, used to schedule prunings.
+    disk_writes: AtomicU64,
+    /// True while a pruning of the on-disk store is running.
+    pruning: Arc<AtomicBool>,
+}
+
+/// In-memory entries of a [ResponseCache] and their access order.
+#[derive(Debug, Default)]
+struct Memory {
+    /// Cached entries by request url.
+    entries: HashMap<String, Entry>,
+    /// Request urls by the value of [Memory::clock] at their last access, oldest first.
+    order: BTreeMap<u64, String>,
+    /// Counter incremented on each access, used to order entries by last access.
+    clock: u64,
+}
+
+impl Memory {
+    /// Get the body of the entry of given key, if still valid, and mark it as used.
+    /// Expired entries are removed.
+    fn get(&mut self, key: &str, now: SystemTime) -> Option<Vec<u8>> {
+        let entry = self.entries.get(key)?;
+        if !entry.is_valid(now) {
+            self.remove(key);
+            return None;
+        }
+        let (last_used, body) = (entry.last_used, entry.body.clone());
+        self.clock += 1;
+        self.order.remove(&last_used);
+        self.order.insert(self.clock, String::from(key));
+        if let Some(entry) = self.entries.get_mut(key) {
+            entry.last_used = self.clock;
+        }
+        Some(body)
+    }
+
+    /// Insert an entry, evicting the least recently used entries beyond given capacity.
+    fn insert(&mut self, capacity: usize, key: &str, mut entry: Entry) {
+        if capacity == 0 {
+            return;
+        }
+        self.remove(key);
+        while self.entries.len() >= capacity {
+            match self.order.pop_first() {
+                Some((_, oldest)) => self.entries.remove(&oldest),
+                None => break,
+            };
+        }
+        self.clock += 1;
+        entry.last_used = self.clock;
+        self.order.insert(self.clock, String::from(key));
+        self.entries.insert(String::from(key), entry);
+    }
+
+    /// Remove the entry of given key, if any.
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.order.remove(&entry.last_used);
+        }
+    }
+
+    /// Remove all entries.
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+}
+
+/// A cached response body.
+#[derive(Debug, Clone)]
+struct Entry {
+    /// Response body.
+    body: Vec<u8>,
+    /// Time the entry expires, None if immutable.
+    expires_at: Option<SystemTime>,
+    /// Value of [Memory::clock] at the last access.
+    last_used: u64,
+}
+
+impl Entry {
+    /// Check if the entry is still valid at given time.
+    fn is_valid(&self, now: SystemTime) -> bool {
+        match self.expires_at {
+            Some(expires_at) => now < expires_at,
+            None => true,
+        }
+    }
+}
+
+impl ResponseCache {
+    /// Construct a [ResponseCache] using the default policies and no on-disk store.
+    ///
+    /// # Arguments
+    /// * capacity: maximum number of responses kept in memory.
+    pub fn new(capacity: usize) -> Self {
+        let ttl = |secs| CachePolicy::Ttl(Duration::from_secs(secs));
+        let policies = [
+            ("/block/:hash", CachePolicy::Immutable),
+            ("/block/:hash/header", CachePolicy::Immutable),
+            ("/block/:hash/txids", CachePolicy::Immutable),
+            ("/block/:hash/txs/:start_index", CachePolicy::Immutable),
+            ("/block/:hash/raw", CachePolicy::Immutable),
+            ("/block/:hash/status", ttl(60)),
+            ("/tx/:txid/hex", CachePolicy::Immutable),
+            ("/tx/:txid/raw", CachePolicy::Immutable),
+            ("/tx/:txid/merkle-proof", ttl(600)),
+            ("/tx/:txid/merkleblock-proof", ttl(600)),
+            ("/blocks/tip/*", ttl(10)),
+            ("/v1/fees/*", ttl(10)),
+            ("/mempool", ttl(10)),
+            ("/mempool/*", ttl(10)),
+            ("/v1/prices", ttl(60)),
+        ];
+        ResponseCache {
+            capacity,
+            directory: None,
+            disk_capacity: DEFAULT_DISK_CAPACITY,
+            policies: policies
+                .into_iter()
+                .map(|(pattern, policy)| (String::from(pattern), policy))
+                .collect(),
+            memory: Mutex::new(Memory::default()),
+            disk_writes: AtomicU64::new(0),
+            pruning: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Set a directory used as on-disk store. It is created on first write.
+    pub fn set_directory(mut self, directory: &Path) -> Self {
+        self.directory = Some(directory.to_path_buf());
+        self
+    }
+
+    /// Set a new maximum number of responses kept in the on-disk store.
+    pub fn set_disk_capacity(mut self, disk_capacity: usize) -> Self {
+        self.disk_capacity = disk_capacity;
+        self
+    }
+
+    /// Set the [CachePolicy] of all endpoint paths matching given pattern, taking precedence
+    /// over all previously set and default policies.
+    pub fn set_policy(mut self, pattern: &str, policy: CachePolicy) -> Self {
+        self.policies.insert(0, (String::from(pattern), policy));
+        self
+    }
+
+    /// Get the maximum number of responses kept in memory.
+    pub fn get_capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Get a reference to the directory of the on-disk store, if any.
+    pub fn get_directory(&self) -> Option<&Path> {
+        self.directory.as_deref()
+    }
+
+    /// Get the maximum number of responses kept in the on-disk store.
+    pub fn get_disk_capacity(&self) -> usize {
+        self.disk_capacity
+    }
+
+    /// Get the [CachePolicy] of given endpoint path.
+    ///
+    /// # Example
+    /// ```
+    /// # use mempool_space::{CachePolicy, ResponseCache};
+    ///
+    /// let cache = ResponseCache::default();
+    /// assert_eq!(cache.get_policy("/block/0000/header"), CachePolicy::Immutable);
+    /// assert_eq!(cache.get_policy("/address/bc1q"), CachePolicy::NoStore);
+    /// ```
+    pub fn get_policy(&self, path: &str) -> CachePolicy {
+        self.policies
+            .iter()
+            .find(|(pattern, _)| matches_pattern(pattern, path))
+            .map_or(CachePolicy::NoStore, |(_, policy)| *policy)
+    }
+
+    /// Get the number of responses kept in memory.
+    pub fn len(&self) -> usize {
+        self.memory.lock().unwrap().entries.len()
+    }
+
+    /// Check if no responses are kept in memory.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove all responses from memory and the on-disk store.
+    ///
+    /// # Notes
+    /// Only files written by the cache are removed, the directory itself and all other
+    /// files within it are kept.
+    pub fn clear(&self) {
+        self.memory.lock().unwrap().clear();
+        for (file_path, _) in self.cache_files() {
+            let _ = fs::remove_file(file_path);
+        }
+    }
+
+    /// Get the cached response body of given endpoint path, if cached and still valid.
+    ///
+    /// # Arguments
+    /// * base_url: base url of the requesting client, separating entries of different APIs.
+    /// * path: the endpoint path, e.g. "/block/:hash".
+    pub(crate) fn get(&self, base_url: &str, path: &str) -> Option<Vec<u8>> {
+        let key = format!("{}{}", base_url, path);
+        let now = SystemTime::now();
+        if let Some(body) = self.memory.lock().unwrap().get(&key, now) {
+            return Some(body);
+        }
+
+        // Fall back to the on-disk store without holding the lock and promote hits into memory
+        let entry = self.read_file(&key, now)?;
+        let body = entry.body.clone();
+        self.memory
+            .lock()
+            .unwrap()
+            .insert(self.capacity, &key, entry);
+        Some(body)
+    }
+
+    /// Store given response body of given endpoint path according to its [CachePolicy].
+    ///
+    /// # Arguments
+    /// * base_url: base url of the requesting client, separating entries of different APIs.
+    /// * path: the endpoint path the policy is looked up for.
+    /// * body: the response body.
+    pub(crate) fn insert(&self, base_url: &str, path: &str, body: &[u8]) {
+        let key = format!("{}{}", base_url, path);
+        let expires_at = match self.get_policy(path) {
+            CachePolicy::NoStore => return,
+            CachePolicy::Ttl(ttl) => Some(SystemTime::now() + ttl),
+            CachePolicy::Immutable => None,
+        };
+        let entry = Entry {
+            body: body.to_vec(),
+            expires_at,
+            last_used: 0,
+        };
+        self.write_file(&key, &entry);
+
+        let mut memory = self.memory.lock().unwrap();
+        memory.insert(self.capacity, &key, entry);
+    }
+
+    /// Read the entry of given key from the on-disk store. Expired and incomplete entries
+    /// are removed.
+    fn read_file(&self, key: &str, now: SystemTime) -> Option<Entry> {
+        let file_path = self.file_path(key)?;
+        let mut reader = BufReader::new(fs::File::open(&file_path).ok()?);
+        let entry = match read_header(&mut reader) {
+            Ok(header) if header.key == key => {
+                let mut body = Vec::new();
+                match reader.read_to_end(&mut body) {
+                    Ok(body_length) if header.is_valid(header.length + body_length as u64, now) => {
+                        Some(Entry {
+                            body,
+                            expires_at: header.expires_at,
+                            last_used: 0,
+                        })
+                    }
+                    _ => None,
+                }
+            }
+            // Note: Entries of colliding keys are kept, they are replaced on insert
+            Ok(_) => return None,
+            Err(_) => None,
+        };
+        if entry.is_none() {
+            let _ = fs::remove_file(file_path);
+        }
+        entry
+    }
+
+    /// Write the entry of given key into the on-disk store.
+    ///
+    /// # Notes
+    /// The entry is written into a temporary file first and renamed afterwards, so readers
+    /// never observe partially written entries.
+    fn write_file(&self, key: &str, entry: &Entry) {
+        let (Some(directory), Some(file_path)) = (&self.directory, self.file_path(key)) else {
+            return;
+        };
+        let expires_at = entry.expires_at.map_or(0, |expires_at| {
+            let secs = expires_at.duration_since(UNIX_EPOCH).unwrap_or_default();
+            secs.as_secs().max(1)
+        });
+        let mut content = format!("{}\n{}\n{}\n", key, expires_at, entry.body.len()).into_bytes();
+        content.extend_from_slice(&entry.body);
+
+        let temp_path = file_path.with_extension(format!(
+            "{}.{}.{}",
+            process::id(),
+            TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed),
+            TEMP_EXTENSION
+        ));
+        let written = fs::create_dir_all(directory)
+            .and_then(|_| fs::write(&temp_path, content))
+            .and_then(|_| fs::rename(&temp_path, file_path));
+        if written.is_err() {
+            let _ = fs::remove_file(temp_path);
+        }
+
+        // u64::is_multiple_of is not available on older toolchains
+        #[allow(clippy::manual_is_multiple_of)]
+        let prune = self.disk_writes.fetch_add(1, Ordering::Relaxed) % DISK_PRUNE_INTERVAL == 0;
+        if prune {
+            self.spawn_prune_files();
+        }
+    }
+
+    /// Prune the on-disk store on a background thread, unless a pruning is already running.
+    fn spawn_prune_files(&self) {
+        let Some(directory) = self.directory.clone() else {
+            return;
+        };
+        if self.pruning.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let (pruning, disk_capacity) = (self.pruning.clone(), self.disk_capacity);
+        let spawned = thread::Builder::new().spawn(move || {
+            prune_files(&directory, disk_capacity);
+            pruning.store(false, Ordering::Release);
+        });
+        if spawned.is_err() {
+            self.pruning.store(false, Ordering::Release);
+        }
+    }
+
+    /// Get all files written by the cache within the on-disk store.
+    ///
+    /// # Returns
+    /// Path of each file and true for entry files, false for temporary files.
+    fn cache_files(&self) -> Vec<(PathBuf, bool)> {
+        match &self.directory {
+            Some(directory) => cache_files(directory),
+            None => Vec::new(),
+        }
+    }
+
+    /// Get the file of given key within the on-disk store, if any.
+    fn file_path(&self, key: &str) -> Option<PathBuf> {
+        let directory = self.directory.as_ref()?;
+        Some(directory.join(format!(
+            "{:016x}.{}",
+            fnv1a(key.as_bytes()),
+            ENTRY_EXTENSION
+        )))
+    }
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        ResponseCache::new(DEFAULT_CACHE_CAPACITY)
+    }
+}
+
+/// Remove expired and incomplete entries, stale temporary files and the oldest entries
+/// beyond given disk capacity from the on-disk store in given directory.
+fn prune_files(directory: &Path, disk_capacity: usize) {
+    let now = SystemTime::now();
+    let mut entries = Vec::new();
+    for (file_path, is_entry) in cache_files(directory) {
+        let modified = fs::metadata(&file_path)
+            .and_then(|metadata| metadata.modified())
+            .unwrap_or(UNIX_EPOCH);
+        if !is_entry {
+            if now.duration_since(modified).unwrap_or_default() > STALE_TEMP_FILE_AGE {
+                let _ = fs::remove_file(file_path);
+            }
+            continue;
+        }
+
+        let valid = fs::File::open(&file_path)
+            .and_then(|file| {
+                let file_length = file.metadata()?.len();
+                let header = read_header(&mut BufReader::new(file))?;
+                Ok(header.is_valid(file_length, now))
+            })
+            .unwrap_or(false);
+        match valid {
+            true => entries.push((modified, file_path)),
+            false => {
+                let _ = fs::remove_file(file_path);
+            }
+        }
+    }
+
+    if entries.len() > disk_capacity {
+        entries.sort();
+        let excess = entries.len() - disk_capacity;
+        for (_, file_path) in entries.into_iter().take(excess) {
+            let _ = fs::remove_file(file_path);
+        }
+    }
+}
+
+/// Get all files written by a [ResponseCache] within given directory.
+///
+/// # Returns
+/// Path of each file and true for entry files, false for temporary files.
+fn cache_files(directory: &Path) -> Vec<(PathBuf, bool)> {
+    let Ok(read_dir) = fs::read_dir(directory) else {
+        return Vec::new();
+    };
+    read_dir
+        .filter_map(|dir_entry| dir_entry.ok())
+        .filter_map(|dir_entry| {
+            let file_name = dir_entry.file_name().into_string().ok()?;
+            let is_entry = is_cache_file_name(&file_name)?;
+            Some((dir_entry.path(), is_entry))
+        })
+        .collect()
+}
+
+/// Header of an entry file.
+///
+/// # Notes
+/// The layout of entry files is: key, expiry in seconds since the unix epoch (0 if
+/// immutable) and body length, each terminated by a newline, followed by the body.
+#[derive(Debug)]
+struct Header {
+    /// Request url of the entry.
+    key: String,
+    /// Time the entry expires, None if immutable.
+    expires_at: Option<SystemTime>,
+    /// Length of the body in bytes.
+    body_length: u64,
+    /// Length of the header in bytes.
+    length: u64,
+}
+
+impl Header {
+    /// Check if an entry file of given length with this header is complete and still valid
+    /// at given time.
+    fn is_valid(&self, file_length: u64, now: SystemTime) -> bool {
+        let valid = match self.expires_at {
+            Some(expires_at) => now < expires_at,
+            None => true,
+        };
+        valid && file_length == self.length + self.body_length
+    }
+}
+
+/// Read the [Header] of an entry file.
+fn read_header(reader: &mut impl BufRead) -> io::Result<Header> {
+    let mut length = 0;
+    let mut read_line = || -> io::Result<String> {
+        let mut line = String::new();
+        length += reader.read_line(&mut line)? as u64;
+        match line.strip_suffix('\n') {
+            Some(line) => Ok(String::from(line)),
+            None => Err(io::Error::from(io::ErrorKind::UnexpectedEof)),
+        }
+    };
+    let invalid = |_| io::Error::from(io::ErrorKind::InvalidData);
+
+    let key = read_line()?;
+    let expires_at: u64 = read_line()?.parse().map_err(invalid)?;
+    let body_length: u64 = read_line()?.parse().map_err(invalid)?;
+    let expires_at = match expires_at {
+        0 => None,
+        secs => Some(
+            UNIX_EPOCH
+                .checked_add(Duration::from_secs(secs))
+                .ok_or(io::Error::from(io::ErrorKind::InvalidData))?,
+        ),
+    };
+    Ok(Header {
+        key,
+        expires_at,
+        body_length,
+        length,
+    })
+}
+
+/// Check if given file name was written by a [ResponseCache].
+///
+/// # Returns
+/// * Some(true) for entry files, "<hash>.entry".
+/// * Some(false) for temporary files, "<hash>.<pid>.<counter>.tmp".
+/// * None for all other files.
+fn is_cache_file_name(file_name: &str) -> Option<bool> {
+    let (hash, extension) = file_name.split_once('.')?;
+    if hash.len() != 16 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    if extension == ENTRY_EXTENSION {
+        return Some(true);
+    }
+    let (pid, rest) = extension.split_once('.')?;
+    let (counter, temp_extension) = rest.split_once('.')?;
+    let is_number = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    match is_number(pid) && is_number(counter) && temp_extension == TEMP_EXTENSION {
+        true => Some(false),
+        false => None,
+    }
+}
+
+/// Check if given endpoint path matches given pattern.
+fn matches_pattern(pattern: &str, path: &str) -> bool {
+    let mut path_segments = path.split('/');
+    for pattern_segment in pattern.split('/') {
+        match (pattern_segment, path_segments.next()) {
+            ("*", Some(segment)) => return !segment.is_empty(),
+            (_, None) => return false,
+            (pattern_segment, Some(segment)) if pattern_segment.starts_with(':') => {
+                if segment.is_empty() {
+                    return false;
+                }
+            }
+            (pattern_segment, Some(segment)) => {
+                if pattern_segment != segment {
+                    return false;
+                }
+            }
+        }
+    }
+    path_segments.next().is_none()
+}
+
+/// Calculate the 64 bit FNV-1a hash of given bytes. Used as stable file name.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+    use std::process::id;
+
+    const URL: &str = "https://mempool.space/api";
+
+    #[test]
+    fn matches_pattern_segments() {
+        // Expectency: Named segments match a single segment, "*" all remaining segments.
+        assert!(matches_pattern("/block/:hash", "/block/00ab"));
+        assert!(!matches_pattern("/block/:hash", "/block/00ab/status"));
+        assert!(!matches_pattern("/block/:hash", "/block/"));
+        assert!(matches_pattern("/v1/fees/*", "/v1/fees/mempool-blocks"));
+        assert!(!matches_pattern("/v1/fees/*", "/v1/fees"));
+        assert!(matches_pattern("/mempool", "/mempool"));
+        assert!(!matches_pattern("/mempool", "/mempool/txids"));
+    }
+
+    #[test]
+    fn response_cache_default_policies() {
+        // Expectency: Confirmed resources are immutable, volatile ones use a short ttl and
+        //             custom policies take precedence.
+        let cache = ResponseCache::default();
+        assert_eq!(cache.get_policy("/block/00ab"), CachePolicy::Immutable);
+        assert_eq!(cache.get_policy("/tx/a1/raw"), CachePolicy::Immutable);
+        assert_eq!(
+            cache.get_policy("/blocks/tip/height"),
+            CachePolicy::Ttl(Duration::from_secs(10))
+        );
+        assert_eq!(cache.get_policy("/tx/a1"), CachePolicy::NoStore);
+
+        let cache = cache.set_policy("/block/:hash", CachePolicy::NoStore);
+        assert_eq!(cache.get_policy("/block/00ab"), CachePolicy::NoStore);
+    }
+
+    #[test]
+    fn response_cache_lru_eviction() {
+        // Expectency: The least recently used entry is evicted once the capacity is reached.
+        let cache = ResponseCache::new(2);
+        cache.insert(URL, "/block/a", b"a");
+        cache.insert(URL, "/block/b", b"b");
+        assert_eq!(cache.get(URL, "/block/a"), Some(b"a".to_vec()));
+        cache.insert(URL, "/block/c", b"c");
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(URL, "/block/b"), None);
+        assert_eq!(cache.get(URL, "/block/a"), Some(b"a".to_vec()));
+        assert_eq!(cache.get(URL, "/block/c"), Some(b"c".to_vec()));
+    }
+
+    #[test]
+    fn response_cache_ttl_and_no_store() {
+        // Expectency: Expired entries and entries without policy are never returned.
+        let cache =
+            ResponseCache::default().set_policy("/v1/prices", CachePolicy::Ttl(Duration::ZERO));
+        cache.insert(URL, "/v1/prices", b"{}");
+        assert_eq!(cache.get(URL, "/v1/prices"), None);
+        cache.insert(URL, "/address/bc1q", b"{}");
+        assert_eq!(cache.get(URL, "/address/bc1q"), None);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn response_cache_disk_store() {
+        // Expectency: Entries written to disk must be available to a new cache instance.
+        let directory = temp_dir().join(format!("mempool_space_cache_test_{}", id()));
+        let cache = ResponseCache::default().set_directory(&directory);
+        cache.insert(URL, "/block/00ab/raw", b"\x00\n\x01");
+        cache.insert(URL, "/blocks/tip/height", b"840000");
+
+        let cache = ResponseCache::default().set_directory(&directory);
+        assert_eq!(
+            cache.get(URL, "/block/00ab/raw"),
+            Some(b"\x00\n\x01".to_vec())
+        );
+        assert_eq!(
+            cache.get(URL, "/blocks/tip/height"),
+            Some(b"840000".to_vec())
+        );
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("http://127.0.0.1/api", "/block/00ab/raw"), None);
+
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(cache.get(URL, "/block/00ab/raw"), None);
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn response_cache_clear_keeps_foreign_files() {
+        // Expectency: Clearing removes only files written by the cache, not the directory
+        //             or other files within it.
+        let directory = temp_dir().join(format!("mempool_space_cache_clear_test_{}", id()));
+        let cache = ResponseCache::default().set_directory(&directory);
+        cache.insert(URL, "/block/00ab", b"{}");
+        let foreign = directory.join("0123456789abcdef.json");
+        fs::write(&foreign, b"{}").unwrap();
+        fs::write(directory.join("notes.entry"), b"").unwrap();
+
+        cache.clear();
+        let mut remaining: Vec<_> = fs::read_dir(&directory)
+            .unwrap()
+            .map(|dir_entry| dir_entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        remaining.sort();
+        assert_eq!(remaining, vec!["0123456789abcdef.json", "notes.entry"]);
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn response_cache_truncated_entry() {
+        // Expectency: Incomplete entries on disk, e.g. after a crash, are never served and
+        //             removed on read.
+        let directory = temp_dir().join(format!("mempool_space_cache_trunc_test_{}", id()));
+        let cache = ResponseCache::default().set_directory(&directory);
+        cache.insert(URL, "/block/00ab/raw", b"0123456789");
+        let file_path = cache.file_path(&format!("{}/block/00ab/raw", URL)).unwrap();
+        let content = fs::read(&file_path).unwrap();
+        fs::write(&file_path, &content[..content.len() - 4]).unwrap();
+
+        let cache = ResponseCache::default().set_directory(&directory);
+        assert_eq!(cache.get(URL, "/block/00ab/raw"), None);
+        assert!(!file_path.exists());
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn response_cache_prune_files() {
+        // Expectency: Pruning removes expired entries, stale temporary files and the oldest
+        //             entries beyond the disk capacity.
+        let directory = temp_dir().join(format!("mempool_space_cache_prune_test_{}", id()));
+        let cache = ResponseCache::default()
+            .set_directory(&directory)
+            .set_disk_capacity(2)
+            .set_policy("/v1/prices", CachePolicy::Ttl(Duration::ZERO));
+        cache.insert(URL, "/v1/prices", b"{}");
+        for hash in ["a", "b", "c"] {
+            cache.insert(URL, &format!("/block/{}", hash), hash.as_bytes());
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        let stale = directory.join("0123456789abcdef.1.2.tmp");
+        fs::write(&stale, b"").unwrap();
+        let stale_time = SystemTime::now() - 2 * STALE_TEMP_FILE_AGE;
+        fs::File::options()
+            .write(true)
+            .open(&stale)
+            .unwrap()
+            .set_modified(stale_time)
+            .unwrap();
+
+        prune_files(&directory, 2);
+        let cache = ResponseCache::default().set_directory(&directory);
+        assert_eq!(cache.get(URL, "/block/a"), None);
+        assert_eq!(cache.get(URL, "/block/b"), Some(b"b".to_vec()));
+        assert_eq!(cache.get(URL, "/block/c"), Some(b"c".to_vec()));
+        assert!(!stale.exists());
+        assert_eq!(fs::read_dir(&directory).unwrap().count(), 2);
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn cache_file_names() {
+        // Expectency: Only entry and temporary files written by the cache are recognized.
+        assert_eq!(is_cache_file_name("0123456789abcdef.entry"), Some(true));
+        assert_eq!(is_cache_file_name("0123456789abcdef.42.7.tmp"), Some(false));
+        assert_eq!(is_cache_file_name("0123456789abcdef.json"), None);
+        assert_eq!(is_cache_file_name("0123456789abcdef.x.7.tmp"), None);
+        assert_eq!(is_cache_file_name("0123.entry"), None);
+        assert_eq!(is_cache_file_name("0123456789abcdef"), None);
+    }
+}
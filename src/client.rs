@@ -0,0 +1,1347 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Module containing a blocking client for the mempool.space REST API.
+
+// Imports
+use super::address::{Address, Utxo};
+use super::block::{Block, BlockStatus};
+use super::cache::ResponseCache;
+use super::difficulty::DifficultyAdjustment;
+use super::endpoint::{self, check_status, Endpoint};
+use super::error::{MempoolError, ParseTargetError};
+use super::failover::{is_failover_error, Backend, BackendPool};
+use super::fees::{MempoolBlock, RecommendedFees};
+use super::lightning::{
+    Channel, LatestNetworkStats, LightningNode, LightningSearch, NodeRanking, TopNode,
+};
+use super::mempool::{MempoolInfo, RecentTransaction};
+use super::mining::{
+    DifficultyEntry, Hashrate, Interval, MiningPools, PoolDetails, PoolHashrate, RewardStats,
+};
+use super::network::Network;
+use super::prices::{Currency, HistoricalPrice, Prices};
+use super::rate_limit::{RateLimiter, RetryPolicy};
+use super::transaction::{MerkleProof, Outspend, Transaction, TxStatus};
+use super::transport::{default_transport, HttpMethod, HttpRequest, HttpResponse, HttpTransport};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use std::vec::IntoIter;
+
+// Documentation imports
+#[cfg(doc)]
+use super::{
+    cache::CachePolicy, error::RejectReason, failover::DEFAULT_CHECK_INTERVAL, target::TcpTarget,
+};
+
+/// Default timeout duration for each request of a [MempoolClient]
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default user agent sent with each request of a [MempoolClient]
+pub const DEFAULT_USER_AGENT: &str = concat!("mempool_space/", env!("CARGO_PKG_VERSION"));
+
+/// Blocking client for the mempool.space REST API.
+///
+/// # Example
+/// ```no_run
+/// # use mempool_space::MempoolClient;
+///
+/// let client = MempoolClient::default();
+/// println!("Current tip: {}", client.get_block_tip_height().unwrap());
+/// ```
+#[derive(Debug)]
+pub struct MempoolClient {
+    /// Base url all endpoint paths are appended to, e.g. "https://mempool.space/api".
+    base_url: String,
+    /// [Duration] used as timeout for each request.
+    timeout: Duration,
+    /// User agent sent with each request.
+    user_agent: String,
+    /// [HttpTransport] performing all requests.
+    transport: Arc<dyn HttpTransport>,
+    /// Cache consulted before each GET request, if any.
+    cache: Option<Arc<ResponseCache>>,
+    /// Limiter delaying each request, if any.
+    rate_limiter: Option<Arc<RateLimiter>>,
+    /// Policy retrying rate limited requests, if any.
+    retry_policy: Option<RetryPolicy>,
+    /// Backends requests are routed to, if constructed from multiple base urls.
+    backends: Option<Arc<BackendPool>>,
+}
+
+impl MempoolClient {
+    /// Construct a [MempoolClient].
+    ///
+    /// # Arguments
+    /// * base_url: url of the API to query, e.g. "https://mempool.space/api".
+    ///
+    /// # Returns
+    /// Instance of [MempoolClient] using [DEFAULT_REQUEST_TIMEOUT] and [DEFAULT_USER_AGENT].
+    ///
+    /// # Notes
+    /// For more convenience use the implementation of trait "From" for [Network].
+    pub fn new(base_url: &str) -> Self {
+        MempoolClient {
+            base_url: String::from(base_url.trim_end_matches('/')),
+            timeout: DEFAULT_REQUEST_TIMEOUT,
+            user_agent: String::from(DEFAULT_USER_AGENT),
+            transport: default_transport(),
+            cache: None,
+            rate_limiter: None,
+            retry_policy: None,
+            backends: None,
+        }
+    }
+
+    /// Construct a [MempoolClient] failing over between multiple backends.
+    ///
+    /// # Arguments
+    /// * base_urls: urls of the APIs to query, in order of preference.
+    ///
+    /// # Returns
+    /// * On success, the [MempoolClient] using [DEFAULT_CHECK_INTERVAL].
+    /// * On failure, a [ParseTargetError] if no base url was given or a base url is malformed.
+    ///
+    /// # Notes
+    /// Backends are checked for availability via [TcpTarget] and queried for their tip
+    /// height once per check interval. Checks run on a separate thread, so requests are not
+    /// delayed by them and meanwhile route by the previous results. Requests are routed to the available backend with the
+    /// highest tip height, ties are resolved by order of preference. Requests failing due to
+    /// transport errors, rate limiting or server errors are repeated on the next backend.
+    ///
+    /// Responses are cached under the first base url, which is reported as base url as well.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use mempool_space::MempoolClient;
+    ///
+    /// let client = MempoolClient::from_base_urls(&[
+    ///     "http://mempool.local:8999/api",
+    ///     "https://mempool.space/api",
+    /// ])
+    /// .unwrap();
+    /// println!("Current tip: {}", client.get_block_tip_height().unwrap());
+    /// ```
+    pub fn from_base_urls(base_urls: &[&str]) -> Result<Self, ParseTargetError> {
+        let backends = BackendPool::new(base_urls)?;
+        let mut client = MempoolClient::new(base_urls[0]);
+        client.backends = Some(Arc::new(backends));
+        Ok(client)
+    }
+
+    /// Set a new timeout [Duration] applied to each request.
+    pub fn set_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set a new user agent sent with each request.
+    pub fn set_user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = String::from(user_agent);
+        self
+    }
+
+    /// Set a new [HttpTransport] performing all requests, e.g. a mock transport in tests.
+    pub fn set_transport(mut self, transport: Arc<dyn HttpTransport>) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Set a [ResponseCache] consulted before each GET request. Responses are stored
+    /// according to the caches [CachePolicy].
+    ///
+    /// # Notes
+    /// A cache may be shared between multiple clients, entries are separated by base url.
+    pub fn set_cache(mut self, cache: Arc<ResponseCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Set a [RateLimiter] each request must take a token from before being sent.
+    ///
+    /// # Notes
+    /// A limiter may be shared between multiple clients to limit their combined request rate.
+    pub fn set_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Set a [RetryPolicy] applied to requests failing with [MempoolError::RateLimited].
+    pub fn set_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Set a new [Duration] between two availability checks of the backends. Only applies
+    /// to clients constructed via [MempoolClient::from_base_urls].
+    pub fn set_check_interval(mut self, check_interval: Duration) -> Self {
+        if let Some(backends) = self.backends.as_mut().and_then(Arc::get_mut) {
+            backends.set_check_interval(check_interval);
+        }
+        self
+    }
+
+    /// Get a reference to the base url in use.
+    pub fn get_base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Get a reference to the timeout [Duration] in use.
+    pub fn get_timeout(&self) -> &Duration {
+        &self.timeout
+    }
+
+    /// Get a reference to the user agent in use.
+    pub fn get_user_agent(&self) -> &str {
+        &self.user_agent
+    }
+
+    /// Get a reference to the [HttpTransport] in use.
+    pub fn get_transport(&self) -> &Arc<dyn HttpTransport> {
+        &self.transport
+    }
+
+    /// Get a reference to the [ResponseCache] in use, if any.
+    pub fn get_cache(&self) -> Option<&Arc<ResponseCache>> {
+        self.cache.as_ref()
+    }
+
+    /// Get a reference to the [RateLimiter] in use, if any.
+    pub fn get_rate_limiter(&self) -> Option<&Arc<RateLimiter>> {
+        self.rate_limiter.as_ref()
+    }
+
+    /// Get a reference to the [RetryPolicy] in use, if any.
+    pub fn get_retry_policy(&self) -> Option<&RetryPolicy> {
+        self.retry_policy.as_ref()
+    }
+
+    /// Get a reference to the [Duration] between two availability checks of the backends,
+    /// if constructed via [MempoolClient::from_base_urls].
+    pub fn get_check_interval(&self) -> Option<&Duration> {
+        self.backends
+            .as_deref()
+            .map(BackendPool::get_check_interval)
+    }
+
+    /// Get a snapshot of all [Backend]s in order of preference. Empty unless constructed
+    /// via [MempoolClient::from_base_urls].
+    pub fn get_backends(&self) -> Vec<Backend> {
+        self.backends
+            .as_deref()
+            .map(BackendPool::get_backends)
+            .unwrap_or_default()
+    }
+
+    /// Get the height of the current chain tip via `GET /blocks/tip/height`.
+    pub fn get_block_tip_height(&self) -> Result<u64, MempoolError> {
+        self.fetch(endpoint::block_tip_height())
+    }
+
+    /// Get the hash of the current chain tip via `GET /blocks/tip/hash`.
+    pub fn get_block_tip_hash(&self) -> Result<String, MempoolError> {
+        self.fetch(endpoint::block_tip_hash())
+    }
+
+    /// Get a [Block] by its hash via `GET /block/:hash`.
+    pub fn get_block(&self, hash: &str) -> Result<Block, MempoolError> {
+        self.fetch(endpoint::block(hash))
+    }
+
+    /// Get the hex encoded header of a block via `GET /block/:hash/header`.
+    pub fn get_block_header(&self, hash: &str) -> Result<String, MempoolError> {
+        self.fetch(endpoint::block_header(hash))
+    }
+
+    /// Get the chain [BlockStatus] of a block via `GET /block/:hash/status`.
+    pub fn get_block_status(&self, hash: &str) -> Result<BlockStatus, MempoolError> {
+        self.fetch(endpoint::block_status(hash))
+    }
+
+    /// Get the txids of all transactions of a block via `GET /block/:hash/txids`.
+    pub fn get_block_txids(&self, hash: &str) -> Result<Vec<String>, MempoolError> {
+        self.fetch(endpoint::block_txids(hash))
+    }
+
+    /// Get a page of 25 transactions of a block via `GET /block/:hash/txs/:start_index`.
+    ///
+    /// # Arguments
+    /// * hash: the hash of the block.
+    /// * start_index: index of the first transaction of the page. Must be a multiple of 25.
+    pub fn get_block_transactions(
+        &self,
+        hash: &str,
+        start_index: u32,
+    ) -> Result<Vec<Transaction>, MempoolError> {
+        self.fetch(endpoint::block_transactions(hash, start_index))
+    }
+
+    /// Get a block as raw bytes via `GET /block/:hash/raw`.
+    pub fn get_block_raw(&self, hash: &str) -> Result<Vec<u8>, MempoolError> {
+        self.fetch(endpoint::block_raw(hash))
+    }
+
+    /// Get the hash of the block at given height of the best chain via `GET /block-height/:height`.
+    pub fn get_block_hash(&self, height: u64) -> Result<String, MempoolError> {
+        self.fetch(endpoint::block_hash(height))
+    }
+
+    /// Get the 10 newest blocks via `GET /blocks/:start_height`.
+    ///
+    /// # Arguments
+    /// * start_height: height of the newest block to return, None for the chain tip.
+    pub fn get_blocks(&self, start_height: Option<u64>) -> Result<Vec<Block>, MempoolError> {
+        self.fetch(endpoint::blocks(start_height))
+    }
+
+    /// Get a [Transaction] by its txid via `GET /tx/:txid`.
+    pub fn get_transaction(&self, txid: &str) -> Result<Transaction, MempoolError> {
+        self.fetch(endpoint::transaction(txid))
+    }
+
+    /// Get the confirmation [TxStatus] of a transaction via `GET /tx/:txid/status`.
+    pub fn get_transaction_status(&self, txid: &str) -> Result<TxStatus, MempoolError> {
+        self.fetch(endpoint::transaction_status(txid))
+    }
+
+    /// Get a transaction hex encoded via `GET /tx/:txid/hex`.
+    pub fn get_transaction_hex(&self, txid: &str) -> Result<String, MempoolError> {
+        self.fetch(endpoint::transaction_hex(txid))
+    }
+
+    /// Get a transaction as raw bytes via `GET /tx/:txid/raw`.
+    pub fn get_transaction_raw(&self, txid: &str) -> Result<Vec<u8>, MempoolError> {
+        self.fetch(endpoint::transaction_raw(txid))
+    }
+
+    /// Get the [MerkleProof] of a confirmed transaction via `GET /tx/:txid/merkle-proof`.
+    pub fn get_transaction_merkle_proof(&self, txid: &str) -> Result<MerkleProof, MempoolError> {
+        self.fetch(endpoint::transaction_merkle_proof(txid))
+    }
+
+    /// Get the hex encoded merkleblock proof of a confirmed transaction via
+    /// `GET /tx/:txid/merkleblock-proof`.
+    ///
+    /// # Notes
+    /// The proof uses the format of bitcoind's `gettxoutproof`.
+    pub fn get_transaction_merkleblock_proof(&self, txid: &str) -> Result<String, MempoolError> {
+        self.fetch(endpoint::transaction_merkleblock_proof(txid))
+    }
+
+    /// Get the [Outspend] of a single transaction output via `GET /tx/:txid/outspend/:vout`.
+    pub fn get_transaction_outspend(
+        &self,
+        txid: &str,
+        vout: u32,
+    ) -> Result<Outspend, MempoolError> {
+        self.fetch(endpoint::transaction_outspend(txid, vout))
+    }
+
+    /// Get the [Outspend] of all transaction outputs via `GET /tx/:txid/outspends`.
+    pub fn get_transaction_outspends(&self, txid: &str) -> Result<Vec<Outspend>, MempoolError> {
+        self.fetch(endpoint::transaction_outspends(txid))
+    }
+
+    /// Get an [Address] summary via `GET /address/:address`.
+    pub fn get_address(&self, address: &str) -> Result<Address, MempoolError> {
+        self.fetch(endpoint::summary(&endpoint::address_path(address)))
+    }
+
+    /// Get the newest transactions of an address via `GET /address/:address/txs`.
+    ///
+    /// # Notes
+    /// Returns up to 50 mempool transactions followed by the newest 25 confirmed transactions.
+    pub fn get_address_transactions(
+        &self,
+        address: &str,
+    ) -> Result<Vec<Transaction>, MempoolError> {
+        self.fetch(endpoint::transactions(&endpoint::address_path(address)))
+    }
+
+    /// Get a page of confirmed transactions of an address via
+    /// `GET /address/:address/txs/chain/:last_seen_txid`.
+    ///
+    /// # Arguments
+    /// * address: the address to get the transactions of.
+    /// * last_seen_txid: txid of the last transaction of the previous page, None for the first page.
+    pub fn get_address_transactions_chain(
+        &self,
+        address: &str,
+        last_seen_txid: Option<&str>,
+    ) -> Result<Vec<Transaction>, MempoolError> {
+        self.fetch(endpoint::transactions_chain(
+            &endpoint::address_path(address),
+            last_seen_txid,
+        ))
+    }
+
+    /// Get the unconfirmed transactions of an address via `GET /address/:address/txs/mempool`.
+    pub fn get_address_transactions_mempool(
+        &self,
+        address: &str,
+    ) -> Result<Vec<Transaction>, MempoolError> {
+        self.fetch(endpoint::transactions_mempool(&endpoint::address_path(
+            address,
+        )))
+    }
+
+    /// Get the unspent outputs of an address via `GET /address/:address/utxo`.
+    pub fn get_address_utxos(&self, address: &str) -> Result<Vec<Utxo>, MempoolError> {
+        self.fetch(endpoint::utxos(&endpoint::address_path(address)))
+    }
+
+    /// Get an iterator over the full confirmed transaction history of an address, newest first.
+    ///
+    /// # Notes
+    /// Pages are requested lazily via `GET /address/:address/txs/chain/:last_seen_txid`.
+    /// See [TransactionHistory] for details.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use mempool_space::MempoolClient;
+    ///
+    /// let client = MempoolClient::default();
+    /// for tx in client.get_address_history("1wiz18xYmhRX6xStj2b9t1rwWX4GKUgpv") {
+    ///     println!("{}", tx.unwrap().txid);
+    /// }
+    /// ```
+    pub fn get_address_history(&self, address: &str) -> TransactionHistory<'_> {
+        TransactionHistory::new(self, endpoint::address_path(address))
+    }
+
+    /// Get an [Address] summary of a scripthash via `GET /scripthash/:hash`.
+    ///
+    /// # Notes
+    /// The scripthash is the hex encoded SHA256 hash of the scriptPubKey in reversed byte order.
+    pub fn get_scripthash(&self, scripthash: &str) -> Result<Address, MempoolError> {
+        self.fetch(endpoint::summary(&endpoint::scripthash_path(scripthash)))
+    }
+
+    /// Get the newest transactions of a scripthash via `GET /scripthash/:hash/txs`.
+    pub fn get_scripthash_transactions(
+        &self,
+        scripthash: &str,
+    ) -> Result<Vec<Transaction>, MempoolError> {
+        self.fetch(endpoint::transactions(&endpoint::scripthash_path(
+            scripthash,
+        )))
+    }
+
+    /// Get a page of confirmed transactions of a scripthash via
+    /// `GET /scripthash/:hash/txs/chain/:last_seen_txid`.
+    ///
+    /// # Arguments
+    /// * scripthash: the scripthash to get the transactions of.
+    /// * last_seen_txid: txid of the last transaction of the previous page, None for the first page.
+    pub fn get_scripthash_transactions_chain(
+        &self,
+        scripthash: &str,
+        last_seen_txid: Option<&str>,
+    ) -> Result<Vec<Transaction>, MempoolError> {
+        self.fetch(endpoint::transactions_chain(
+            &endpoint::scripthash_path(scripthash),
+            last_seen_txid,
+        ))
+    }
+
+    /// Get the unconfirmed transactions of a scripthash via `GET /scripthash/:hash/txs/mempool`.
+    pub fn get_scripthash_transactions_mempool(
+        &self,
+        scripthash: &str,
+    ) -> Result<Vec<Transaction>, MempoolError> {
+        self.fetch(endpoint::transactions_mempool(&endpoint::scripthash_path(
+            scripthash,
+        )))
+    }
+
+    /// Get the unspent outputs of a scripthash via `GET /scripthash/:hash/utxo`.
+    pub fn get_scripthash_utxos(&self, scripthash: &str) -> Result<Vec<Utxo>, MempoolError> {
+        self.fetch(endpoint::utxos(&endpoint::scripthash_path(scripthash)))
+    }
+
+    /// Get an iterator over the full confirmed transaction history of a scripthash, newest first.
+    ///
+    /// # Notes
+    /// See [get_address_history](MempoolClient::get_address_history).
+    pub fn get_scripthash_history(&self, scripthash: &str) -> TransactionHistory<'_> {
+        TransactionHistory::new(self, endpoint::scripthash_path(scripthash))
+    }
+
+    /// Broadcast a signed transaction via `POST /tx`.
+    ///
+    /// # Arguments
+    /// * raw_hex: the hex encoded signed transaction.
+    ///
+    /// # Returns
+    /// * On success, the txid of the broadcast transaction.
+    /// * On rejection by the node, [MempoolError::Rejected] with the classified [RejectReason].
+    /// * On any other failure, the corresponding [MempoolError].
+    pub fn broadcast(&self, raw_hex: &str) -> Result<String, MempoolError> {
+        self.fetch(endpoint::broadcast(raw_hex))
+            .map_err(endpoint::broadcast_error)
+    }
+
+    /// Get the current mempool backlog statistics via `GET /mempool`.
+    pub fn get_mempool(&self) -> Result<MempoolInfo, MempoolError> {
+        self.fetch(endpoint::mempool())
+    }
+
+    /// Get the txids of all mempool transactions via `GET /mempool/txids`.
+    pub fn get_mempool_txids(&self) -> Result<Vec<String>, MempoolError> {
+        self.fetch(endpoint::mempool_txids())
+    }
+
+    /// Get the last 10 transactions added to the mempool via `GET /mempool/recent`.
+    pub fn get_mempool_recent(&self) -> Result<Vec<RecentTransaction>, MempoolError> {
+        self.fetch(endpoint::mempool_recent())
+    }
+
+    /// Get the currently recommended feerates via `GET /v1/fees/recommended`.
+    pub fn get_recommended_fees(&self) -> Result<RecommendedFees, MempoolError> {
+        self.fetch(endpoint::recommended_fees())
+    }
+
+    /// Get the blocks projected from the current mempool via `GET /v1/fees/mempool-blocks`.
+    ///
+    /// # Notes
+    /// Use [feerate_for_target](crate::fees::feerate_for_target) to pick a feerate for a
+    /// confirmation target from the returned blocks.
+    pub fn get_mempool_blocks(&self) -> Result<Vec<MempoolBlock>, MempoolError> {
+        self.fetch(endpoint::mempool_blocks())
+    }
+
+    /// Get the progress of the current difficulty epoch via `GET /v1/difficulty-adjustment`.
+    pub fn get_difficulty_adjustment(&self) -> Result<DifficultyAdjustment, MempoolError> {
+        self.fetch(endpoint::difficulty_adjustment())
+    }
+
+    /// Get the block counts of all mining pools within given [Interval] via
+    /// `GET /v1/mining/pools/:interval`.
+    pub fn get_mining_pools(&self, interval: Interval) -> Result<MiningPools, MempoolError> {
+        self.fetch(endpoint::mining_pools(interval))
+    }
+
+    /// Get the [PoolDetails] of a mining pool via `GET /v1/mining/pool/:slug`.
+    ///
+    /// # Arguments
+    /// * slug: the slug of the mining pool as listed by [PoolStats](crate::mining::PoolStats), e.g. "foundryusa".
+    pub fn get_mining_pool(&self, slug: &str) -> Result<PoolDetails, MempoolError> {
+        self.fetch(endpoint::mining_pool(slug))
+    }
+
+    /// Get the hashrate history of a mining pool via `GET /v1/mining/pool/:slug/hashrate`.
+    pub fn get_mining_pool_hashrate(&self, slug: &str) -> Result<Vec<PoolHashrate>, MempoolError> {
+        self.fetch(endpoint::mining_pool_hashrate(slug))
+    }
+
+    /// Get the 10 newest blocks mined by a mining pool via `GET /v1/mining/pool/:slug/blocks/:height`.
+    ///
+    /// # Arguments
+    /// * slug: the slug of the mining pool.
+    /// * before_height: only return blocks below this height, None for the newest blocks.
+    pub fn get_mining_pool_blocks(
+        &self,
+        slug: &str,
+        before_height: Option<u64>,
+    ) -> Result<Vec<Block>, MempoolError> {
+        self.fetch(endpoint::mining_pool_blocks(slug, before_height))
+    }
+
+    /// Get the network [Hashrate] and difficulty within given [Interval] via
+    /// `GET /v1/mining/hashrate/:interval`.
+    pub fn get_hashrate(&self, interval: Interval) -> Result<Hashrate, MempoolError> {
+        self.fetch(endpoint::hashrate(interval))
+    }
+
+    /// Get past difficulty adjustments, newest first, via
+    /// `GET /v1/mining/difficulty-adjustments/:interval`.
+    ///
+    /// # Arguments
+    /// * interval: the [Interval] to get the adjustments of, None for all adjustments.
+    pub fn get_difficulty_adjustments(
+        &self,
+        interval: Option<Interval>,
+    ) -> Result<Vec<DifficultyEntry>, MempoolError> {
+        self.fetch(endpoint::difficulty_adjustments(interval))
+    }
+
+    /// Get the [RewardStats] of the given number of newest blocks via
+    /// `GET /v1/mining/reward-stats/:blockCount`.
+    pub fn get_reward_stats(&self, block_count: u32) -> Result<RewardStats, MempoolError> {
+        self.fetch(endpoint::reward_stats(block_count))
+    }
+
+    /// Get the latest lightning [NetworkStats] via `GET /v1/lightning/statistics/latest`.
+    pub fn get_lightning_statistics(&self) -> Result<LatestNetworkStats, MempoolError> {
+        self.fetch(endpoint::lightning_statistics())
+    }
+
+    /// Search lightning nodes by alias or public key and channels by short channel id via
+    /// `GET /v1/lightning/search?searchText=:query`.
+    pub fn search_lightning(&self, query: &str) -> Result<LightningSearch, MempoolError> {
+        self.fetch(endpoint::search_lightning(query))
+    }
+
+    /// Get a [LightningNode] by its public key via `GET /v1/lightning/nodes/:public_key`.
+    pub fn get_lightning_node(&self, public_key: &str) -> Result<LightningNode, MempoolError> {
+        self.fetch(endpoint::lightning_node(public_key))
+    }
+
+    /// Get a lightning [Channel] by its id via `GET /v1/lightning/channels/:short_id`.
+    ///
+    /// # Arguments
+    /// * short_id: the short channel id, e.g. "698925x2156x0", or the channel id as integer.
+    pub fn get_lightning_channel(&self, short_id: &str) -> Result<Channel, MempoolError> {
+        self.fetch(endpoint::lightning_channel(short_id))
+    }
+
+    /// Get the top 100 lightning nodes of given [NodeRanking] via
+    /// `GET /v1/lightning/nodes/rankings/:ranking`.
+    pub fn get_lightning_top_nodes(
+        &self,
+        ranking: NodeRanking,
+    ) -> Result<Vec<TopNode>, MempoolError> {
+        self.fetch(endpoint::lightning_top_nodes(ranking))
+    }
+
+    /// Get the current bitcoin [Prices] via `GET /v1/prices`.
+    pub fn get_prices(&self) -> Result<Prices, MempoolError> {
+        self.fetch(endpoint::prices())
+    }
+
+    /// Get the bitcoin price closest to given timestamp via `GET /v1/historical-price`.
+    ///
+    /// # Arguments
+    /// * currency: the [Currency] to get the price in.
+    /// * timestamp: point in time in seconds since the unix epoch, e.g. a blocks timestamp.
+    pub fn get_historical_price(
+        &self,
+        currency: Currency,
+        timestamp: u64,
+    ) -> Result<HistoricalPrice, MempoolError> {
+        self.fetch(endpoint::historical_price(currency, timestamp))
+    }
+
+    /// Request given [Endpoint] and decode its response body. Responses of GET requests are
+    /// served from and stored into the [ResponseCache] if set.
+    fn fetch<T>(&self, endpoint: Endpoint<T>) -> Result<T, MempoolError> {
+        let cache = match endpoint.get_method() {
+            HttpMethod::Get => self.cache.as_deref(),
+            _ => None,
+        };
+        let path = endpoint.get_path();
+        if let Some(body) = cache.and_then(|cache| cache.get(&self.base_url, path)) {
+            return endpoint.decode(body);
+        }
+        let response =
+            self.route(|base_url| endpoint.request(base_url, self.timeout, &self.user_agent))?;
+        let body = response.into_body();
+        if let Some(cache) = cache {
+            cache.insert(&self.base_url, path, &body);
+        }
+        endpoint.decode(body)
+    }
+
+    /// Send the request built for a base url to the first backend answering it. Without
+    /// backends, the request is sent to the base url.
+    fn route(&self, request: impl Fn(&str) -> HttpRequest) -> Result<HttpResponse, MempoolError> {
+        let backends = match &self.backends {
+            Some(backends) => backends,
+            None => return self.send(&request(&self.base_url)),
+        };
+        if backends.claim_check() {
+            // Check in the background, meanwhile requests are routed by the previous results
+            let (backends, transport) = (backends.clone(), self.transport.clone());
+            let (timeout, user_agent) = (self.timeout, self.user_agent.clone());
+            thread::spawn(move || backends.check(transport.as_ref(), timeout, &user_agent));
+        }
+
+        let mut last_error = None;
+        for (index, base_url) in backends.route() {
+            match self.send(&request(&base_url)) {
+                Err(error) if is_failover_error(&error) => {
+                    backends.mark_failed(index);
+                    last_error = Some(error);
+                }
+                result => return result,
+            }
+        }
+        Err(last_error.expect("BackendPool contains at least one backend"))
+    }
+
+    /// Send given request once the [RateLimiter] permits, failing on non-success status codes.
+    /// Rate limited requests are retried according to the [RetryPolicy].
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse, MempoolError> {
+        let mut retry = 0;
+        loop {
+            if let Some(rate_limiter) = &self.rate_limiter {
+                thread::sleep(rate_limiter.reserve());
+            }
+            let retry_after = match check_status(self.transport.send(request)?) {
+                Err(MempoolError::RateLimited { retry_after }) => retry_after,
+                result => return result,
+            };
+            match self
+                .retry_policy
+                .as_ref()
+                .and_then(|policy| policy.get_delay(retry, retry_after))
+            {
+                Some(delay) => thread::sleep(delay),
+                None => return Err(MempoolError::RateLimited { retry_after }),
+            }
+            retry += 1;
+        }
+    }
+}
+
+/// Iterator over the confirmed transaction history of an address or scripthash, newest first.
+///
+/// Walks the `txs/chain/:last_seen_txid` pagination of the API, requesting the next page
+/// once all transactions of the current page were yielded.
+///
+/// # Notes
+/// The iterator ends after the first empty page. A failed request is yielded as error once,
+/// afterwards the iterator ends as well.
+#[derive(Debug)]
+pub struct TransactionHistory<'a> {
+    /// Client used to request pages.
+    client: &'a MempoolClient,
+    /// Endpoint path of the address or scripthash, e.g. "/address/:address".
+    path: String,
+    /// Txid of the last transaction yielded, if any.
+    last_seen_txid: Option<String>,
+    /// Remaining transactions of the current page.
+    page: IntoIter<Transaction>,
+    /// True once the history is exhausted or a request failed.
+    done: bool,
+}
+
+impl<'a> TransactionHistory<'a> {
+    /// Construct a [TransactionHistory] starting at the newest transaction of given endpoint path.
+    fn new(client: &'a MempoolClient, path: String) -> Self {
+        TransactionHistory {
+            client,
+            path,
+            last_seen_txid: None,
+            page: Vec::new().into_iter(),
+            done: false,
+        }
+    }
+}
+
+impl Iterator for TransactionHistory<'_> {
+    type Item = Result<Transaction, MempoolError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(tx) = self.page.next() {
+            self.last_seen_txid = Some(tx.txid.clone());
+            return Some(Ok(tx));
+        }
+        if self.done {
+            return None;
+        }
+
+        let endpoint = endpoint::transactions_chain(&self.path, self.last_seen_txid.as_deref());
+        match self.client.fetch(endpoint) {
+            Ok(page) if page.is_empty() => {
+                self.done = true;
+                None
+            }
+            Ok(page) => {
+                self.page = page.into_iter();
+                self.next()
+            }
+            Err(error) => {
+                self.done = true;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+impl From<Network> for MempoolClient {
+    fn from(network: Network) -> Self {
+        MempoolClient::new(network.base_url())
+    }
+}
+
+impl Default for MempoolClient {
+    fn default() -> Self {
+        MempoolClient::from(Network::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::RejectReason;
+    use crate::failover::DEFAULT_CHECK_INTERVAL;
+    use crate::target::Status;
+    use crate::test_utils::{closed_addr, response, serve, transaction_page};
+    use crate::transport::MockHttpTransport;
+
+    #[test]
+    fn mempool_client_new() {
+        // Expectency: Trailing slashes of the base url must be removed and all
+        //             other settings must be initialized with their defaults.
+        let client = MempoolClient::new("http://127.0.0.1:8999/api/");
+        assert_eq!(client.get_base_url(), "http://127.0.0.1:8999/api");
+        assert_eq!(client.get_timeout(), &DEFAULT_REQUEST_TIMEOUT);
+        assert_eq!(client.get_user_agent(), DEFAULT_USER_AGENT);
+
+        let client = MempoolClient::default()
+            .set_timeout(Duration::from_secs(1))
+            .set_user_agent("test-agent");
+        assert_eq!(client.get_base_url(), "https://mempool.space/api");
+        assert_eq!(client.get_timeout(), &Duration::from_secs(1));
+        assert_eq!(client.get_user_agent(), "test-agent");
+    }
+
+    #[test]
+    fn mempool_client_from_network() {
+        // Expectency: A client constructed from a network must use the networks base url.
+        let client = MempoolClient::from(Network::Signet);
+        assert_eq!(client.get_base_url(), "https://mempool.space/signet/api");
+
+        let client = MempoolClient::from(Network::Custom(String::from("http://10.0.0.2/api")));
+        assert_eq!(client.get_base_url(), "http://10.0.0.2/api");
+    }
+
+    #[test]
+    fn mempool_client_get_block_tip_height() {
+        // Expectency: The tip height must be requested from the configured base url with
+        //             the configured user agent.
+        let (base_url, srv) = serve(vec![response(200, "840000")]);
+        let client = MempoolClient::new(&format!("{}/api", base_url)).set_user_agent("test-agent");
+        assert_eq!(client.get_block_tip_height().unwrap(), 840000);
+
+        let requests = srv.join().unwrap();
+        assert!(requests[0].starts_with("GET /api/blocks/tip/height HTTP/1.1"));
+        assert!(requests[0]
+            .to_lowercase()
+            .contains("user-agent: test-agent"));
+    }
+
+    #[test]
+    fn mempool_client_get_recommended_fees() {
+        // Expectency: JSON responses must be deserialized into their typed counterparts.
+        let body = r#"{"fastestFee":3,"halfHourFee":2,"hourFee":2,"economyFee":1,"minimumFee":1}"#;
+        let (base_url, srv) = serve(vec![response(200, body)]);
+        let fees = MempoolClient::new(&base_url)
+            .get_recommended_fees()
+            .unwrap();
+        assert_eq!(fees.fastest_fee, 3.0);
+        assert_eq!(fees.minimum_fee, 1.0);
+
+        let requests = srv.join().unwrap();
+        assert!(requests[0].starts_with("GET /v1/fees/recommended HTTP/1.1"));
+    }
+
+    #[test]
+    fn mempool_client_transaction_endpoints() {
+        // Expectency: Each transaction endpoint must request its path and decode its body.
+        let txid = "15e10745f15593a899cef391191bdd3d7c12412cc4696b7bcb669d0feadc8521";
+        let (base_url, srv) = serve(vec![
+            response(200, r#"{"confirmed":true,"block_height":840000}"#),
+            response(200, "0100"),
+            response(200, "\x01\x00"),
+            response(200, r#"{"block_height":840000,"merkle":[],"pos":0}"#),
+            response(200, "0000"),
+            response(200, r#"{"spent":false}"#),
+            response(
+                200,
+                r#"[{"spent":false},{"spent":true,"txid":"ab","vin":0}]"#,
+            ),
+        ]);
+        let client = MempoolClient::new(&base_url);
+        let status = client.get_transaction_status(txid).unwrap();
+        assert_eq!(status.block_height, Some(840000));
+        assert_eq!(client.get_transaction_hex(txid).unwrap(), "0100");
+        assert_eq!(client.get_transaction_raw(txid).unwrap(), vec![1, 0]);
+        assert_eq!(client.get_transaction_merkle_proof(txid).unwrap().pos, 0);
+        assert_eq!(
+            client.get_transaction_merkleblock_proof(txid).unwrap(),
+            "0000"
+        );
+        assert!(!client.get_transaction_outspend(txid, 1).unwrap().spent);
+        let outspends = client.get_transaction_outspends(txid).unwrap();
+        assert_eq!(outspends[1].vin, Some(0));
+
+        let requests = srv.join().unwrap();
+        let paths = [
+            "status",
+            "hex",
+            "raw",
+            "merkle-proof",
+            "merkleblock-proof",
+            "outspend/1",
+            "outspends",
+        ];
+        for (request, path) in requests.iter().zip(paths) {
+            assert!(request.starts_with(&format!("GET /tx/{}/{} HTTP/1.1", txid, path)));
+        }
+    }
+
+    #[test]
+    fn mempool_client_broadcast() {
+        // Expectency: The raw transaction must be posted as body and the txid returned.
+        let txid = "15e10745f15593a899cef391191bdd3d7c12412cc4696b7bcb669d0feadc8521";
+        let (base_url, srv) = serve(vec![response(200, txid)]);
+        let client = MempoolClient::new(&base_url);
+        assert_eq!(client.broadcast("0100000001\n").unwrap(), txid);
+
+        let requests = srv.join().unwrap();
+        assert!(requests[0].starts_with("POST /tx HTTP/1.1"));
+        assert!(requests[0].ends_with("\r\n\r\n0100000001"));
+    }
+
+    #[test]
+    fn mempool_client_broadcast_rejected() {
+        // Expectency: Rejections of the node must be classified, other failures passed through.
+        let message = r#"sendrawtransaction RPC error: {"code":-26,"message":"min relay fee not met, 100 < 141"}"#;
+        let (base_url, srv) = serve(vec![response(400, message), response(502, "")]);
+        let client = MempoolClient::new(&base_url);
+        match client.broadcast("0100").unwrap_err() {
+            MempoolError::Rejected(reason, body) => {
+                assert_eq!(reason, RejectReason::MinRelayFeeNotMet);
+                assert_eq!(body, message);
+            }
+            error => panic!("Unexpected error: {}", error),
+        }
+        assert!(matches!(
+            client.broadcast("0100").unwrap_err(),
+            MempoolError::HttpStatus(502, _)
+        ));
+        srv.join().unwrap();
+    }
+
+    #[test]
+    fn mempool_client_address_endpoints() {
+        // Expectency: Each address and scripthash endpoint must request its path.
+        let (base_url, srv) = serve(vec![
+            response(200, &transaction_page(&["a1"])),
+            response(200, &transaction_page(&["a2"])),
+            response(200, "[]"),
+            response(
+                200,
+                r#"[{"txid":"a1","vout":1,"status":{"confirmed":false},"value":5}]"#,
+            ),
+            response(200, &transaction_page(&[])),
+            response(200, "[]"),
+        ]);
+        let client = MempoolClient::new(&base_url);
+        assert_eq!(
+            client.get_address_transactions("bc1q").unwrap()[0].txid,
+            "a1"
+        );
+        let page = client
+            .get_address_transactions_chain("bc1q", Some("a1"))
+            .unwrap();
+        assert_eq!(page[0].txid, "a2");
+        assert!(client
+            .get_address_transactions_mempool("bc1q")
+            .unwrap()
+            .is_empty());
+        assert_eq!(client.get_address_utxos("bc1q").unwrap()[0].value, 5);
+        assert!(client
+            .get_scripthash_transactions_chain("8ba3", None)
+            .unwrap()
+            .is_empty());
+        assert!(client.get_scripthash_utxos("8ba3").unwrap().is_empty());
+
+        let requests = srv.join().unwrap();
+        let paths = [
+            "/address/bc1q/txs",
+            "/address/bc1q/txs/chain/a1",
+            "/address/bc1q/txs/mempool",
+            "/address/bc1q/utxo",
+            "/scripthash/8ba3/txs/chain",
+            "/scripthash/8ba3/utxo",
+        ];
+        for (request, path) in requests.iter().zip(paths) {
+            assert!(request.starts_with(&format!("GET {} HTTP/1.1", path)));
+        }
+    }
+
+    #[test]
+    fn mempool_client_address_history() {
+        // Expectency: The history must walk all pages using the last seen txid as cursor
+        //             and end on the first empty page.
+        let (base_url, srv) = serve(vec![
+            response(200, &transaction_page(&["a1", "a2"])),
+            response(200, &transaction_page(&["a3"])),
+            response(200, "[]"),
+        ]);
+        let client = MempoolClient::new(&base_url);
+        let txids: Vec<String> = client
+            .get_address_history("bc1q")
+            .map(|tx| tx.unwrap().txid)
+            .collect();
+        assert_eq!(txids, vec!["a1", "a2", "a3"]);
+
+        let requests = srv.join().unwrap();
+        assert!(requests[0].starts_with("GET /address/bc1q/txs/chain HTTP/1.1"));
+        assert!(requests[1].starts_with("GET /address/bc1q/txs/chain/a2 HTTP/1.1"));
+        assert!(requests[2].starts_with("GET /address/bc1q/txs/chain/a3 HTTP/1.1"));
+    }
+
+    #[test]
+    fn mempool_client_scripthash_history_error() {
+        // Expectency: A failed page request must be yielded once and end the history.
+        let (base_url, srv) = serve(vec![
+            response(200, &transaction_page(&["a1"])),
+            response(500, ""),
+        ]);
+        let client = MempoolClient::new(&base_url);
+        let mut history = client.get_scripthash_history("8ba3");
+        assert_eq!(history.next().unwrap().unwrap().txid, "a1");
+        assert!(matches!(
+            history.next(),
+            Some(Err(MempoolError::HttpStatus(500, _)))
+        ));
+        assert!(history.next().is_none());
+        srv.join().unwrap();
+    }
+
+    #[test]
+    fn mempool_client_block_endpoints() {
+        // Expectency: Each block endpoint must request its path and decode its body.
+        let hash = "000000000000000015dc777b3ff2611091336355d3f0ee9766a2cf3be8e4b1ce";
+        let (base_url, srv) = serve(vec![
+            response(200, "0200"),
+            response(200, r#"{"in_best_chain":true,"height":363366}"#),
+            response(200, r#"["a1","a2"]"#),
+            response(200, &transaction_page(&["a26"])),
+            response(200, "\x02\x00"),
+            response(200, hash),
+            response(200, "[]"),
+            response(200, "[]"),
+        ]);
+        let client = MempoolClient::new(&base_url);
+        assert_eq!(client.get_block_header(hash).unwrap(), "0200");
+        assert_eq!(client.get_block_status(hash).unwrap().height, Some(363366));
+        assert_eq!(client.get_block_txids(hash).unwrap(), vec!["a1", "a2"]);
+        assert_eq!(
+            client.get_block_transactions(hash, 25).unwrap()[0].txid,
+            "a26"
+        );
+        assert_eq!(client.get_block_raw(hash).unwrap(), vec![2, 0]);
+        assert_eq!(client.get_block_hash(363366).unwrap(), hash);
+        assert!(client.get_blocks(None).unwrap().is_empty());
+        assert!(client.get_blocks(Some(363366)).unwrap().is_empty());
+
+        let requests = srv.join().unwrap();
+        let block_path = format!("/block/{}", hash);
+        let paths = [
+            format!("{}/header", block_path),
+            format!("{}/status", block_path),
+            format!("{}/txids", block_path),
+            format!("{}/txs/25", block_path),
+            format!("{}/raw", block_path),
+            String::from("/block-height/363366"),
+            String::from("/blocks"),
+            String::from("/blocks/363366"),
+        ];
+        for (request, path) in requests.iter().zip(paths) {
+            assert!(request.starts_with(&format!("GET {} HTTP/1.1", path)));
+        }
+    }
+
+    #[test]
+    fn mempool_client_mining_endpoints() {
+        // Expectency: Each mining endpoint must request its path including the interval.
+        let (base_url, srv) = serve(vec![
+            response(
+                200,
+                r#"{"pools":[],"blockCount":0,"lastEstimatedHashrate":0}"#,
+            ),
+            response(200, "[]"),
+            response(200, "[]"),
+            response(200, "[]"),
+            response(200, r#"[[1703311464,822528,72006146478567.1,1.06983]]"#),
+            response(200, "[]"),
+        ]);
+        let client = MempoolClient::new(&base_url);
+        assert!(client
+            .get_mining_pools(Interval::Week)
+            .unwrap()
+            .pools
+            .is_empty());
+        assert!(client
+            .get_mining_pool_hashrate("foundryusa")
+            .unwrap()
+            .is_empty());
+        assert!(client
+            .get_mining_pool_blocks("foundryusa", None)
+            .unwrap()
+            .is_empty());
+        assert!(client
+            .get_mining_pool_blocks("foundryusa", Some(840000))
+            .unwrap()
+            .is_empty());
+        let adjustments = client
+            .get_difficulty_adjustments(Some(Interval::Day))
+            .unwrap();
+        assert_eq!(adjustments[0].height, 822528);
+        assert!(client.get_difficulty_adjustments(None).unwrap().is_empty());
+
+        let requests = srv.join().unwrap();
+        let paths = [
+            "/v1/mining/pools/1w",
+            "/v1/mining/pool/foundryusa/hashrate",
+            "/v1/mining/pool/foundryusa/blocks",
+            "/v1/mining/pool/foundryusa/blocks/840000",
+            "/v1/mining/difficulty-adjustments/24h",
+            "/v1/mining/difficulty-adjustments",
+        ];
+        for (request, path) in requests.iter().zip(paths) {
+            assert!(request.starts_with(&format!("GET {} HTTP/1.1", path)));
+        }
+    }
+
+    #[test]
+    fn mempool_client_lightning_endpoints() {
+        // Expectency: Each lightning endpoint must request its path, search queries encoded.
+        let (base_url, srv) = serve(vec![
+            response(
+                200,
+                r#"{"nodes":[{"public_key":"03ab","alias":"ACINQ"}],"channels":[]}"#,
+            ),
+            response(200, "[]"),
+        ]);
+        let client = MempoolClient::new(&base_url);
+        let search = client.search_lightning("ACINQ & co").unwrap();
+        assert_eq!(search.nodes[0].alias, "ACINQ");
+        assert!(client
+            .get_lightning_top_nodes(NodeRanking::Connectivity)
+            .unwrap()
+            .is_empty());
+
+        let requests = srv.join().unwrap();
+        assert!(requests[0].starts_with("GET /v1/lightning/search?searchText=ACINQ%20%26%20co "));
+        assert!(requests[1].starts_with("GET /v1/lightning/nodes/rankings/connectivity "));
+    }
+
+    #[test]
+    fn mempool_client_mempool_endpoints() {
+        // Expectency: Each mempool endpoint must request its path and decode its body.
+        let recent = r#"[{"txid":"a1","fee":18277,"vsize":2585,"value":4972029}]"#;
+        let (base_url, srv) = serve(vec![response(200, r#"["a1","a2"]"#), response(200, recent)]);
+        let client = MempoolClient::new(&base_url);
+        assert_eq!(client.get_mempool_txids().unwrap(), vec!["a1", "a2"]);
+        assert_eq!(client.get_mempool_recent().unwrap()[0].fee, 18277);
+
+        let requests = srv.join().unwrap();
+        assert!(requests[0].starts_with("GET /mempool/txids HTTP/1.1"));
+        assert!(requests[1].starts_with("GET /mempool/recent HTTP/1.1"));
+    }
+
+    #[test]
+    fn mempool_client_cache() {
+        // Expectency: Cacheable responses are served from the cache, others and failed
+        //             requests are always requested.
+        let hash = "000000000000000015dc777b3ff2611091336355d3f0ee9766a2cf3be8e4b1ce";
+        let (base_url, srv) = serve(vec![
+            response(200, "0200"),
+            response(200, "[]"),
+            response(200, "[]"),
+            response(500, ""),
+        ]);
+        let cache = Arc::new(ResponseCache::default());
+        let client = MempoolClient::new(&base_url).set_cache(cache.clone());
+        assert_eq!(client.get_block_header(hash).unwrap(), "0200");
+        assert_eq!(client.get_block_header(hash).unwrap(), "0200");
+        assert!(client.get_address_utxos("bc1q").unwrap().is_empty());
+        assert!(client.get_address_utxos("bc1q").unwrap().is_empty());
+        assert!(client.get_block_header("00ab").is_err());
+        assert_eq!(cache.len(), 1);
+
+        let requests = srv.join().unwrap();
+        assert_eq!(requests.len(), 4);
+        assert!(requests[3].starts_with("GET /block/00ab/header HTTP/1.1"));
+    }
+
+    #[test]
+    fn mempool_client_get_historical_price() {
+        // Expectency: Currency and timestamp must be passed as query parameters.
+        let body = r#"{"prices":[{"time":1499904000,"EUR":1964}],"exchangeRates":{}}"#;
+        let (base_url, srv) = serve(vec![response(200, body)]);
+        let historical = MempoolClient::new(&base_url)
+            .get_historical_price(Currency::Eur, 1500000000)
+            .unwrap();
+        assert_eq!(historical.get(Currency::Eur), Some(1964.0));
+
+        let requests = srv.join().unwrap();
+        assert!(requests[0]
+            .starts_with("GET /v1/historical-price?currency=EUR&timestamp=1500000000 HTTP/1.1"));
+    }
+
+    #[test]
+    fn mempool_client_http_error_status() {
+        // Expectency: Non-success status codes must be reported as error.
+        let (base_url, srv) = serve(vec![response(404, "Transaction not found")]);
+        let error = MempoolClient::new(&base_url)
+            .get_transaction("00")
+            .unwrap_err();
+        assert_eq!(
+            format!("{}", error),
+            "HTTP status 404: Transaction not found"
+        );
+        srv.join().unwrap();
+    }
+
+    #[test]
+    fn mempool_client_rate_limited() {
+        // Expectency: HTTP status 429 must be reported as RateLimited error containing the
+        //             duration announced via the Retry-After header.
+        let (base_url, srv) = serve(vec![String::from(
+            "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 12\r\nContent-Length: 0\r\n\r\n",
+        )]);
+        match MempoolClient::new(&base_url).get_block_tip_height() {
+            Err(MempoolError::RateLimited { retry_after }) => {
+                assert_eq!(retry_after, Some(Duration::from_secs(12)))
+            }
+            other => panic!("Unexpected result {:?}", other),
+        }
+        srv.join().unwrap();
+    }
+
+    #[test]
+    fn mempool_client_service_unavailable() {
+        // Expectency: HTTP status 503 must be reported as RateLimited error as well.
+        let (base_url, srv) = serve(vec![response(503, "")]);
+        match MempoolClient::new(&base_url).get_block_tip_height() {
+            Err(MempoolError::RateLimited { retry_after }) => assert_eq!(retry_after, None),
+            other => panic!("Unexpected result {:?}", other),
+        }
+        srv.join().unwrap();
+    }
+
+    #[test]
+    fn mempool_client_retry() {
+        // Expectency: Rate limited requests are retried until the retry policy is exhausted.
+        let rate_limited = "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 0\r\n\
+                            Content-Length: 0\r\nConnection: close\r\n\r\n";
+        let (base_url, srv) = serve(vec![
+            String::from(rate_limited),
+            response(503, ""),
+            response(200, "840000"),
+            String::from(rate_limited),
+            String::from(rate_limited),
+            String::from(rate_limited),
+        ]);
+        let policy = RetryPolicy::new(2).set_base_delay(Duration::from_millis(10));
+        let client = MempoolClient::new(&base_url).set_retry_policy(policy);
+        assert_eq!(client.get_block_tip_height().unwrap(), 840000);
+        assert!(matches!(
+            client.get_block_tip_hash(),
+            Err(MempoolError::RateLimited { .. })
+        ));
+        assert_eq!(srv.join().unwrap().len(), 6);
+    }
+
+    #[test]
+    fn mempool_client_rate_limiter() {
+        // Expectency: Requests exceeding the burst are delayed by the rate limiter.
+        let (base_url, srv) = serve(vec![response(200, "1"), response(200, "2")]);
+        let client = MempoolClient::new(&base_url)
+            .set_rate_limiter(Arc::new(RateLimiter::new(10.0, 1).unwrap()));
+        let start = std::time::Instant::now();
+        assert_eq!(client.get_block_tip_height().unwrap(), 1);
+        assert_eq!(client.get_block_tip_height().unwrap(), 2);
+        assert!(start.elapsed() >= Duration::from_millis(90));
+        srv.join().unwrap();
+    }
+
+    #[test]
+    fn mempool_client_failover() {
+        // Expectency: Requests are routed to the available backend with the highest tip and
+        //             fail over to the next backend on server errors.
+        let unreachable = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}", listener.local_addr().unwrap())
+        };
+        let (lagging_url, lagging) = serve(vec![response(200, "99"), response(200, "hash")]);
+        let (leading_url, leading) = serve(vec![response(200, "100"), response(502, "")]);
+        let client =
+            MempoolClient::from_base_urls(&[&unreachable, &lagging_url, &leading_url]).unwrap();
+        assert_eq!(client.get_base_url(), unreachable);
+        assert_eq!(client.get_check_interval(), Some(&DEFAULT_CHECK_INTERVAL));
+
+        // Check up front, so the request routes by its results instead of claiming a check
+        let backends = client.backends.as_ref().unwrap();
+        assert!(backends.claim_check());
+        backends.check(
+            client.transport.as_ref(),
+            client.timeout,
+            &client.user_agent,
+        );
+
+        assert_eq!(client.get_block_tip_hash().unwrap(), "hash");
+        let backends = client.get_backends();
+        assert_eq!(backends[0].get_status(), &Status::NotAvailable);
+        assert_eq!(backends[1].get_tip_height(), Some(99));
+        assert_eq!(backends[2].get_status(), &Status::NotAvailable);
+        assert_eq!(backends[2].get_tip_height(), Some(100));
+
+        assert!(lagging.join().unwrap()[1].starts_with("GET /blocks/tip/hash HTTP/1.1"));
+        assert!(leading.join().unwrap()[1].starts_with("GET /blocks/tip/hash HTTP/1.1"));
+        assert!(MempoolClient::from_base_urls(&[]).is_err());
+    }
+
+    #[test]
+    fn mempool_client_failover_background_check() {
+        // Expectency: The first request claims an availability check running in the
+        //             background, its results are stored once finished.
+        let unreachable = format!("http://{}", closed_addr());
+        let (base_url, srv) = serve(vec![response(200, "840000"), response(200, "840000")]);
+        let client = MempoolClient::from_base_urls(&[&unreachable, &base_url]).unwrap();
+        assert_eq!(client.get_block_tip_height().unwrap(), 840000);
+
+        while client
+            .get_backends()
+            .iter()
+            .any(|backend| backend.get_status() == &Status::Unknown)
+        {
+            thread::sleep(Duration::from_millis(10));
+        }
+        let backends = client.get_backends();
+        assert_eq!(backends[0].get_status(), &Status::NotAvailable);
+        assert_eq!(backends[1].get_status(), &Status::Available);
+        assert_eq!(backends[1].get_tip_height(), Some(840000));
+        assert_eq!(srv.join().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn mempool_client_transport() {
+        // Expectency: All requests are performed by the injected transport.
+        let mut transport = MockHttpTransport::new();
+        transport
+            .expect_send()
+            .withf(|request| {
+                request.get_method() == HttpMethod::Get
+                    && request.get_url() == "http://mock/api/blocks/tip/height"
+                    && request.get_headers()[0]
+                        == (String::from("User-Agent"), String::from("mock"))
+                    && request.get_timeout() == Some(&Duration::from_secs(1))
+            })
+            .times(1)
+            .returning(|_| Ok(HttpResponse::new(200, b"840000".to_vec())));
+        let client = MempoolClient::new("http://mock/api")
+            .set_user_agent("mock")
+            .set_timeout(Duration::from_secs(1))
+            .set_transport(Arc::new(transport));
+        assert_eq!(client.get_block_tip_height().unwrap(), 840000);
+    }
+
+    #[test]
+    fn mempool_client_decode_error() {
+        // Expectency: A malformed response body must be reported as decode error.
+        let (base_url, srv) = serve(vec![response(200, "<html>maintenance</html>")]);
+        let error = MempoolClient::new(&base_url).get_mempool().unwrap_err();
+        assert!(matches!(error, MempoolError::Decode(_, _)));
+        srv.join().unwrap();
+    }
+}
@@ -0,0 +1,245 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Module containing difficulty adjustment types and offline halving/epoch calculations.
+
+// Imports
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Number of blocks between two subsidy halvings
+pub const HALVING_INTERVAL: u64 = 210_000;
+
+/// Number of blocks between two difficulty adjustments
+pub const DIFFICULTY_ADJUSTMENT_INTERVAL: u64 = 2016;
+
+/// Expected [Duration] between two blocks
+pub const TARGET_BLOCK_TIME: Duration = Duration::from_secs(600);
+
+/// Block subsidy of the first halving epoch in satoshis
+pub const INITIAL_SUBSIDY: u64 = 50 * 100_000_000;
+
+/// Progress of the current difficulty epoch as returned by `GET /v1/difficulty-adjustment`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DifficultyAdjustment {
+    /// Progress of the current epoch in percent
+    pub progress_percent: f64,
+    /// Expected difficulty change at the next retarget in percent
+    pub difficulty_change: f64,
+    /// Estimated date of the next retarget in milliseconds since the unix epoch
+    pub estimated_retarget_date: u64,
+    /// Number of blocks until the next retarget
+    pub remaining_blocks: u64,
+    /// Estimated time until the next retarget in milliseconds
+    pub remaining_time: u64,
+    /// Difficulty change of the previous retarget in percent
+    pub previous_retarget: f64,
+    /// Height of the next retarget
+    pub next_retarget_height: u64,
+    /// Average block time of the current epoch in milliseconds
+    pub time_avg: u64,
+    /// Time offset used on testnet in milliseconds
+    #[serde(default)]
+    pub time_offset: i64,
+}
+
+impl DifficultyAdjustment {
+    /// Get the estimated time until the next retarget as [Duration].
+    pub fn remaining_duration(&self) -> Duration {
+        Duration::from_millis(self.remaining_time)
+    }
+}
+
+/// Position of a block height within its difficulty epoch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Epoch {
+    /// Number of the difficulty epoch, starting at 0
+    pub epoch: u64,
+    /// Height of the first block of the epoch
+    pub start_height: u64,
+    /// Height of the first block of the next epoch
+    pub next_retarget_height: u64,
+    /// Number of blocks until the next retarget
+    pub remaining_blocks: u64,
+}
+
+impl Epoch {
+    /// Calculate the difficulty [Epoch] of given block height.
+    ///
+    /// # Example
+    /// ```
+    /// # use mempool_space::difficulty::Epoch;
+    ///
+    /// let epoch = Epoch::from_height(840_000);
+    /// assert_eq!(epoch.next_retarget_height, 840_672);
+    /// assert_eq!(epoch.remaining_blocks, 672);
+    /// ```
+    pub fn from_height(height: u64) -> Self {
+        let epoch = height / DIFFICULTY_ADJUSTMENT_INTERVAL;
+        let start_height = epoch * DIFFICULTY_ADJUSTMENT_INTERVAL;
+        let next_retarget_height = start_height.saturating_add(DIFFICULTY_ADJUSTMENT_INTERVAL);
+        Epoch {
+            epoch,
+            start_height,
+            next_retarget_height,
+            remaining_blocks: next_retarget_height - height,
+        }
+    }
+
+    /// Get the progress of the epoch in percent.
+    pub fn progress_percent(&self) -> f64 {
+        let mined = DIFFICULTY_ADJUSTMENT_INTERVAL - self.remaining_blocks;
+        mined as f64 * 100.0 / DIFFICULTY_ADJUSTMENT_INTERVAL as f64
+    }
+
+    /// Get the estimated time until the next retarget assuming [TARGET_BLOCK_TIME].
+    pub fn estimated_remaining_time(&self) -> Duration {
+        estimate_duration(self.remaining_blocks)
+    }
+}
+
+/// Position of a block height within its halving epoch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Halving {
+    /// Number of halvings that occurred up to the block height
+    pub halvings: u64,
+    /// Height of the next halving
+    pub next_halving_height: u64,
+    /// Number of blocks until the next halving
+    pub remaining_blocks: u64,
+    /// Subsidy of the block height in satoshis
+    pub current_subsidy: u64,
+    /// Subsidy after the next halving in satoshis
+    pub next_subsidy: u64,
+}
+
+impl Halving {
+    /// Calculate the [Halving] epoch of given block height.
+    ///
+    /// # Example
+    /// ```
+    /// # use mempool_space::difficulty::Halving;
+    ///
+    /// let halving = Halving::from_height(840_000);
+    /// assert_eq!(halving.halvings, 4);
+    /// assert_eq!(halving.next_halving_height, 1_050_000);
+    /// assert_eq!(halving.current_subsidy, 312_500_000);
+    /// ```
+    pub fn from_height(height: u64) -> Self {
+        let halvings = height / HALVING_INTERVAL;
+        let next_halving_height = (halvings + 1).saturating_mul(HALVING_INTERVAL);
+        Halving {
+            halvings,
+            next_halving_height,
+            remaining_blocks: next_halving_height - height,
+            current_subsidy: block_subsidy(height),
+            next_subsidy: block_subsidy(next_halving_height),
+        }
+    }
+
+    /// Get the estimated time until the next halving assuming [TARGET_BLOCK_TIME].
+    pub fn estimated_remaining_time(&self) -> Duration {
+        estimate_duration(self.remaining_blocks)
+    }
+}
+
+/// Calculate the block subsidy in satoshis of given block height.
+pub fn block_subsidy(height: u64) -> u64 {
+    let halvings = height / HALVING_INTERVAL;
+    if halvings >= 64 {
+        0
+    } else {
+        INITIAL_SUBSIDY >> halvings
+    }
+}
+
+/// Estimate the [Duration] it takes to mine the given number of blocks.
+fn estimate_duration(blocks: u64) -> Duration {
+    TARGET_BLOCK_TIME * blocks as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn difficulty_adjustment_deserialize() {
+        // Expectency: The camel case keys of the API must map onto DifficultyAdjustment.
+        let json = r#"{
+            "progressPercent": 44.397,
+            "difficultyChange": 98.45,
+            "estimatedRetargetDate": 1627762478000,
+            "remainingBlocks": 1121,
+            "remainingTime": 665977000,
+            "previousRetarget": -4.807,
+            "previousTime": 1627086007,
+            "nextRetargetHeight": 741888,
+            "timeAvg": 302328,
+            "adjustedTimeAvg": 302328,
+            "timeOffset": 0,
+            "expectedBlocks": 1025.6
+        }"#;
+        let adjustment: DifficultyAdjustment = serde_json::from_str(json).unwrap();
+        assert_eq!(adjustment.remaining_blocks, 1121);
+        assert_eq!(adjustment.next_retarget_height, 741888);
+        assert_eq!(adjustment.previous_retarget, -4.807);
+        assert_eq!(adjustment.remaining_duration(), Duration::from_secs(665977));
+    }
+
+    #[test]
+    fn epoch_from_height() {
+        // Expectency: Retarget boundaries start a new epoch with all blocks remaining.
+        let epoch = Epoch::from_height(0);
+        assert_eq!(epoch.epoch, 0);
+        assert_eq!(epoch.remaining_blocks, DIFFICULTY_ADJUSTMENT_INTERVAL);
+        assert_eq!(epoch.progress_percent(), 0.0);
+
+        let epoch = Epoch::from_height(2015);
+        assert_eq!(epoch.epoch, 0);
+        assert_eq!(epoch.next_retarget_height, 2016);
+        assert_eq!(epoch.remaining_blocks, 1);
+        assert_eq!(epoch.estimated_remaining_time(), Duration::from_secs(600));
+
+        let epoch = Epoch::from_height(2016 + 1008);
+        assert_eq!(epoch.epoch, 1);
+        assert_eq!(epoch.start_height, 2016);
+        assert_eq!(epoch.progress_percent(), 50.0);
+    }
+
+    #[test]
+    fn halving_from_height() {
+        // Expectency: Halving heights and subsidies must follow the consensus schedule.
+        let halving = Halving::from_height(209_999);
+        assert_eq!(halving.halvings, 0);
+        assert_eq!(halving.remaining_blocks, 1);
+        assert_eq!(halving.current_subsidy, 5_000_000_000);
+        assert_eq!(halving.next_subsidy, 2_500_000_000);
+
+        let halving = Halving::from_height(630_000);
+        assert_eq!(halving.halvings, 3);
+        assert_eq!(halving.next_halving_height, 840_000);
+        assert_eq!(halving.remaining_blocks, 210_000);
+        assert_eq!(
+            halving.estimated_remaining_time(),
+            Duration::from_secs(210_000 * 600)
+        );
+
+        // Heights within the last halving epoch must not overflow
+        let halving = Halving::from_height(u64::MAX);
+        assert_eq!(halving.next_halving_height, u64::MAX);
+        assert_eq!(halving.remaining_blocks, 0);
+        assert_eq!(halving.next_subsidy, 0);
+        assert_eq!(Epoch::from_height(u64::MAX).next_retarget_height, u64::MAX);
+    }
+
+    #[test]
+    fn block_subsidy_schedule() {
+        // Expectency: The subsidy halves every 210000 blocks and drops to 0 after 64 halvings.
+        assert_eq!(block_subsidy(0), 5_000_000_000);
+        assert_eq!(block_subsidy(840_000), 312_500_000);
+        assert_eq!(block_subsidy(HALVING_INTERVAL * 33), 0);
+        assert_eq!(block_subsidy(HALVING_INTERVAL * 64), 0);
+    }
+}
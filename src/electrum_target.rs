@@ -0,0 +1,603 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Module containing a "Target" speaking the Electrum JSON-RPC protocol.
+
+// Imports
+use super::target::{
+    invalid_data, probe_addrs, Fqhn, Port, Status, Target, DEFAULT_TCP_CONNECT_TIMEOUT,
+};
+use super::{CheckTargetError, ParseTargetError, ResolvePolicy, TcpTarget};
+#[cfg(feature = "electrum-tls")]
+use rustls::client::{ServerCertVerified, ServerCertVerifier};
+#[cfg(feature = "electrum-tls")]
+use rustls::{
+    Certificate, ClientConfig, ClientConnection, OwnedTrustAnchor, RootCertStore, ServerName,
+    StreamOwned,
+};
+use serde_json::{json, Value};
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::str::FromStr;
+#[cfg(feature = "electrum-tls")]
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+#[cfg(feature = "electrum-tls")]
+use std::time::SystemTime;
+
+/// Client name announced in the server.version request of an [ElectrumTarget]
+pub const ELECTRUM_CLIENT_NAME: &str = concat!("mempool_space ", env!("CARGO_PKG_VERSION"));
+
+/// Electrum protocol version requested by an [ElectrumTarget]
+pub const ELECTRUM_PROTOCOL_VERSION: &str = "1.4";
+
+/// Maximum number of lines read until the expected response must be received
+const MAX_RESPONSE_LINES: usize = 16;
+
+/// Maximum length of a single line accepted from the server
+const MAX_LINE_LENGTH: u64 = 64 * 1024;
+
+/// Length of a hex encoded block header
+const HEADER_HEX_LENGTH: usize = 160;
+
+/// Transport used to connect to an Electrum server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElectrumTransport {
+    /// Plain TCP, usually on port 50001.
+    Tcp,
+    /// TLS over TCP, usually on port 50002. Requires feature "electrum-tls".
+    Ssl,
+}
+
+impl fmt::Display for ElectrumTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElectrumTransport::Tcp => write!(f, "electrum"),
+            ElectrumTransport::Ssl => write!(f, "electrum+ssl"),
+        }
+    }
+}
+
+/// Information a server announced during the last successful check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElectrumServerInfo {
+    /// Software of the server, e.g. "Fulcrum 1.10.0"
+    pub server_software: String,
+    /// Negotiated protocol version, e.g. "1.4"
+    pub protocol_version: String,
+    /// Height of the chain tip of the server
+    pub tip_height: u64,
+}
+
+/// Target to check if an Electrum or Fulcrum server answers with a sane chain tip.
+///
+/// # Notes
+/// ElectrumTargets connect to the server, negotiate the protocol version via `server.version`
+/// and request the chain tip via `blockchain.headers.subscribe`. Servers are available if they
+/// answer both requests with a tip height above zero and a complete block header. The
+/// [ElectrumServerInfo] of the last successful check is kept.
+///
+/// Electrum servers often use self-signed certificates. These are rejected unless invalid
+/// certificates are explicitly accepted via [ElectrumTarget::set_accept_invalid_certs].
+///
+/// Like [TcpTarget], the checks block up to connect_timeout per address and request.
+///
+/// # Example
+/// ```no_run
+/// # use std::str::FromStr;
+/// # use mempool_space::{ElectrumTarget, Status, Target};
+///
+/// let target = ElectrumTarget::from_str("electrum+ssl://electrum.blockstream.info:50002")
+///     .unwrap();
+/// if target.check_availability().unwrap() == Status::Available {
+///     println!("{:?}", target.get_server_info());
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ElectrumTarget {
+    /// [Fqhn] specifying the server to connect to.
+    fqhn: Fqhn,
+    /// [Port] specifying the port to connect to.
+    port: Port,
+    /// [ElectrumTransport] used to connect to the server.
+    transport: ElectrumTransport,
+    /// [Duration] used as connect_timeout and as timeout of each read and write.
+    connect_timeout: Duration,
+    /// [ResolvePolicy] to apply during resolution of fqhn to IP addresses.
+    resolve_policy: ResolvePolicy,
+    /// Accept invalid TLS certificates, e.g. self-signed ones.
+    accept_invalid_certs: bool,
+    /// [ElectrumServerInfo] of the last successful check.
+    server_info: Mutex<Option<ElectrumServerInfo>>,
+}
+
+impl ElectrumTarget {
+    /// Construct an [ElectrumTarget].
+    ///
+    /// # Arguments
+    /// * fqhn: string containing "fully qualified domain name" e.g. "::1", "localhost".
+    /// * port: port number to connect to.
+    /// * transport: the [ElectrumTransport] to connect with.
+    /// * connect_timeout: [Duration] used as connection attempt and request timeout.
+    /// * resolve_policy: the [ResolvePolicy] to use for this [Target].
+    ///
+    /// # Returns
+    /// Instance of [ElectrumTarget] rejecting invalid TLS certificates.
+    ///
+    /// # Notes
+    /// For more convenience use the implementations of trait "From" and "FromStr".
+    pub fn new(
+        fqhn: Fqhn,
+        port: Port,
+        transport: ElectrumTransport,
+        connect_timeout: Duration,
+        resolve_policy: ResolvePolicy,
+    ) -> Self {
+        ElectrumTarget {
+            fqhn,
+            port,
+            transport,
+            connect_timeout,
+            resolve_policy,
+            accept_invalid_certs: false,
+            server_info: Mutex::new(None),
+        }
+    }
+
+    /// Set a new [ElectrumTransport] to connect with.
+    pub fn set_transport(mut self, transport: ElectrumTransport) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Accept invalid TLS certificates, e.g. self-signed ones, if set to true.
+    pub fn set_accept_invalid_certs(mut self, accept_invalid_certs: bool) -> Self {
+        self.accept_invalid_certs = accept_invalid_certs;
+        self
+    }
+
+    /// Set a new [ResolvePolicy] for name resolution.
+    pub fn set_resolve_policy(mut self, resolve_policy: ResolvePolicy) -> Self {
+        self.resolve_policy = resolve_policy;
+        self
+    }
+
+    /// Set a new connect_timeout [Duration] used for connection attempts and each request.
+    pub fn set_connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = connect_timeout;
+        self
+    }
+
+    /// Get a reference to the [Fqhn].
+    pub fn get_fqhn(&self) -> &Fqhn {
+        &self.fqhn
+    }
+
+    /// Get a reference to the [Port] number in use.
+    pub fn get_portnumber(&self) -> &Port {
+        &self.port
+    }
+
+    /// Get a reference to the [ElectrumTransport] in use.
+    pub fn get_transport(&self) -> &ElectrumTransport {
+        &self.transport
+    }
+
+    /// Get if invalid TLS certificates are accepted.
+    pub fn get_accept_invalid_certs(&self) -> bool {
+        self.accept_invalid_certs
+    }
+
+    /// Get a reference to the connect_timeout [Duration] in use.
+    pub fn get_connect_timeout(&self) -> &Duration {
+        &self.connect_timeout
+    }
+
+    /// Get a reference to the [ResolvePolicy] in use.
+    pub fn get_resolve_policy(&self) -> &ResolvePolicy {
+        &self.resolve_policy
+    }
+
+    /// Get the [ElectrumServerInfo] of the last successful check, if any.
+    pub fn get_server_info(&self) -> Option<ElectrumServerInfo> {
+        self.server_info.lock().unwrap().clone()
+    }
+
+    /// Connect to given address and query version and chain tip of the server.
+    ///
+    /// # Returns
+    /// * On success, the [ElectrumServerInfo] of the server.
+    /// * On failure, an [io::Error] if the connection or TLS handshake failed or the server
+    ///   misbehaved.
+    fn query(&self, addr: SocketAddr) -> io::Result<ElectrumServerInfo> {
+        let stream = TcpStream::connect_timeout(&addr, self.connect_timeout)?;
+        stream.set_read_timeout(Some(self.connect_timeout))?;
+        stream.set_write_timeout(Some(self.connect_timeout))?;
+
+        match self.transport {
+            ElectrumTransport::Tcp => query_server(stream),
+            #[cfg(not(feature = "electrum-tls"))]
+            ElectrumTransport::Ssl => Err(io::Error::from(io::ErrorKind::Unsupported)),
+            #[cfg(feature = "electrum-tls")]
+            ElectrumTransport::Ssl => {
+                let server_name = ServerName::try_from(self.fqhn.as_str())
+                    .map_err(|_| invalid_data("Invalid TLS server name"))?;
+                let connection =
+                    ClientConnection::new(tls_config(self.accept_invalid_certs), server_name)
+                        .map_err(io::Error::other)?;
+                query_server(StreamOwned::new(connection, stream))
+            }
+        }
+    }
+}
+
+impl Target for ElectrumTarget {
+    fn get_id(&self) -> String {
+        match self.fqhn.contains(':') {
+            true => format!("{}://[{}]:{}", self.transport, self.fqhn, self.port),
+            false => format!("{}://{}:{}", self.transport, self.fqhn, self.port),
+        }
+    }
+
+    fn check_availability(&self) -> Result<Status, CheckTargetError> {
+        #[cfg(not(feature = "electrum-tls"))]
+        if self.transport == ElectrumTransport::Ssl {
+            return Err(CheckTargetError::from("TLS requires feature electrum-tls"));
+        }
+        let server_info = probe_addrs(&self.resolve_policy, &self.fqhn, self.port, |addr| {
+            self.query(addr)
+        })?;
+        match server_info {
+            Some(server_info) => {
+                *self.server_info.lock().unwrap() = Some(server_info);
+                Ok(Status::Available)
+            }
+            None => Ok(Status::NotAvailable),
+        }
+    }
+}
+
+impl From<SocketAddr> for ElectrumTarget {
+    /// Build an [ElectrumTarget] connecting via plain TCP to given address.
+    fn from(socket: SocketAddr) -> Self {
+        let target = TcpTarget::from(socket);
+        ElectrumTarget::new(
+            target.get_fqhn().clone(),
+            *target.get_portnumber(),
+            ElectrumTransport::Tcp,
+            DEFAULT_TCP_CONNECT_TIMEOUT,
+            *target.get_resolve_policy(),
+        )
+    }
+}
+
+impl FromStr for ElectrumTarget {
+    type Err = ParseTargetError;
+
+    /// Parse an [ElectrumTarget] from "host:port" with an optional scheme.
+    ///
+    /// # Notes
+    /// The schemes "electrum", "electrum+tcp" and "tcp" select [ElectrumTransport::Tcp],
+    /// the schemes "electrum+ssl", "electrum+tls", "ssl" and "tls" select
+    /// [ElectrumTransport::Ssl]. Without scheme, plain TCP is used.
+    fn from_str(s: &str) -> Result<ElectrumTarget, Self::Err> {
+        let (transport, authority) = match s.split_once("://") {
+            Some((scheme, authority)) => {
+                let transport = match scheme.to_lowercase().as_str() {
+                    "electrum" | "electrum+tcp" | "tcp" => ElectrumTransport::Tcp,
+                    "electrum+ssl" | "electrum+tls" | "ssl" | "tls" => ElectrumTransport::Ssl,
+                    _ => return Err(ParseTargetError::from("Unsupported scheme")),
+                };
+                (transport, authority.trim_end_matches('/'))
+            }
+            None => (ElectrumTransport::Tcp, s),
+        };
+
+        // Note: IPv6 addresses may be enclosed in brackets, e.g. "[::1]:50001"
+        let target = TcpTarget::from_str(authority)?;
+        let fqhn = target.get_fqhn();
+        let fqhn = match fqhn.strip_prefix('[') {
+            Some(bracketed) => bracketed
+                .strip_suffix(']')
+                .ok_or(ParseTargetError::from("Missing ']' after IPv6 address"))?,
+            None => fqhn,
+        };
+        if fqhn.is_empty() {
+            return Err(ParseTargetError::from("No FQHN found"));
+        }
+
+        Ok(ElectrumTarget::new(
+            String::from(fqhn),
+            *target.get_portnumber(),
+            transport,
+            DEFAULT_TCP_CONNECT_TIMEOUT,
+            *target.get_resolve_policy(),
+        ))
+    }
+}
+
+/// [ServerCertVerifier] accepting any certificate.
+#[cfg(feature = "electrum-tls")]
+struct AcceptAnyCertificate;
+
+#[cfg(feature = "electrum-tls")]
+impl ServerCertVerifier for AcceptAnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+/// Build the TLS configuration verifying certificates against the webpki roots.
+#[cfg(feature = "electrum-tls")]
+fn tls_config(accept_invalid_certs: bool) -> Arc<ClientConfig> {
+    let mut root_store = RootCertStore::empty();
+    root_store.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|ta| {
+        OwnedTrustAnchor::from_subject_spki_name_constraints(
+            ta.subject,
+            ta.spki,
+            ta.name_constraints,
+        )
+    }));
+    let mut config = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(root_store)
+        .with_no_client_auth();
+    if accept_invalid_certs {
+        config
+            .dangerous()
+            .set_certificate_verifier(Arc::new(AcceptAnyCertificate));
+    }
+    Arc::new(config)
+}
+
+/// Query version and chain tip of the server connected via given stream.
+fn query_server(stream: impl Read + Write) -> io::Result<ElectrumServerInfo> {
+    let mut reader = BufReader::new(stream);
+    let version = request(
+        &mut reader,
+        0,
+        "server.version",
+        json!([ELECTRUM_CLIENT_NAME, ELECTRUM_PROTOCOL_VERSION]),
+    )?;
+    let (server_software, protocol_version) = match version.as_array().map(Vec::as_slice) {
+        Some([Value::String(software), Value::String(protocol)]) => {
+            (software.clone(), protocol.clone())
+        }
+        _ => return Err(invalid_data("Malformed server.version response")),
+    };
+
+    let header = request(&mut reader, 1, "blockchain.headers.subscribe", json!([]))?;
+    Ok(ElectrumServerInfo {
+        server_software,
+        protocol_version,
+        tip_height: parse_tip_height(&header)?,
+    })
+}
+
+/// Send a JSON-RPC request and read its response.
+///
+/// # Returns
+/// * On success, the result of the request.
+/// * On failure, an [io::Error] if the connection failed, the server answered with an error
+///   or did not answer within [MAX_RESPONSE_LINES] lines.
+fn request<S: Read + Write>(
+    reader: &mut BufReader<S>,
+    id: u64,
+    method: &str,
+    params: Value,
+) -> io::Result<Value> {
+    let request = json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params});
+    let mut line = request.to_string();
+    line.push('\n');
+    reader.get_mut().write_all(line.as_bytes())?;
+    reader.get_mut().flush()?;
+
+    for _ in 0..MAX_RESPONSE_LINES {
+        let mut line = String::new();
+        if reader.by_ref().take(MAX_LINE_LENGTH).read_line(&mut line)? == 0 {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+        }
+        let mut response: Value = serde_json::from_str(&line)?;
+
+        // Note: Servers may send notifications, e.g. of new blocks, in between
+        if response["id"] != id {
+            continue;
+        }
+        if !response["error"].is_null() {
+            return Err(invalid_data("Server answered with an error"));
+        }
+        return Ok(response["result"].take());
+    }
+    Err(invalid_data("No response received"))
+}
+
+/// Parse the tip height from the result of blockchain.headers.subscribe.
+///
+/// # Returns
+/// * On success, the tip height.
+/// * On failure, an [io::Error] if the height is zero or the header is incomplete.
+fn parse_tip_height(header: &Value) -> io::Result<u64> {
+    let height = header["height"].as_u64().unwrap_or_default();
+    let hex = header["hex"].as_str().unwrap_or_default();
+    if height == 0 {
+        return Err(invalid_data("Insane tip height"));
+    }
+    if hex.len() != HEADER_HEX_LENGTH || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid_data("Malformed tip header"));
+    }
+    Ok(height)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, TcpListener};
+    use std::thread::{spawn, JoinHandle};
+
+    use super::*;
+    use crate::test_utils::closed_addr;
+
+    /// Spawn a fake server answering each request line with the next of given responses.
+    fn fake_server(responses: Vec<String>) -> (SocketAddr, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handle = spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut methods = Vec::new();
+            for response in responses {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or_default() == 0 {
+                    break;
+                }
+                match serde_json::from_str::<Value>(&line) {
+                    Ok(request) => methods.push(String::from(request["method"].as_str().unwrap())),
+                    Err(_) => break,
+                }
+                reader.get_mut().write_all(response.as_bytes()).unwrap();
+            }
+            methods
+        });
+        (addr, handle)
+    }
+
+    fn version_response() -> String {
+        String::from("{\"jsonrpc\":\"2.0\",\"id\":0,\"result\":[\"Fulcrum 1.10.0\",\"1.4\"]}\n")
+    }
+
+    fn header_response(height: u64) -> String {
+        format!(
+            "{{\"jsonrpc\":\"2.0\",\"id\":1,\"result\":{{\"hex\":\"{}\",\"height\":{}}}}}\n",
+            "00".repeat(80),
+            height
+        )
+    }
+
+    #[test]
+    fn electrum_target_from_str() {
+        // Expectency: ElectrumTargets are parsed from "host:port" with optional scheme.
+        let target = ElectrumTarget::from_str("electrum+ssl://electrum.example.com:50002").unwrap();
+        assert_eq!(target.get_fqhn(), "electrum.example.com");
+        assert_eq!(target.get_portnumber(), &50002);
+        assert_eq!(target.get_transport(), &ElectrumTransport::Ssl);
+        assert!(!target.get_accept_invalid_certs());
+        assert_eq!(target.get_connect_timeout(), &DEFAULT_TCP_CONNECT_TIMEOUT);
+        assert_eq!(target.get_resolve_policy(), &ResolvePolicy::Agnostic);
+        assert_eq!(target.get_id(), "electrum+ssl://electrum.example.com:50002");
+
+        let target = ElectrumTarget::from_str("electrum://[::1]:50001/").unwrap();
+        assert_eq!(target.get_fqhn(), "::1");
+        assert_eq!(target.get_transport(), &ElectrumTransport::Tcp);
+        assert_eq!(target.get_id(), "electrum://[::1]:50001");
+
+        let target = ElectrumTarget::from_str("localhost:50001").unwrap();
+        assert_eq!(target.get_transport(), &ElectrumTransport::Tcp);
+
+        assert!(ElectrumTarget::from_str("electrum+ssl://localhost").is_err());
+        assert!(ElectrumTarget::from_str("https://localhost:50002").is_err());
+        assert!(ElectrumTarget::from_str("electrum://[::1:50001").is_err());
+        assert!(ElectrumTarget::from_str("electrum://[]:50001").is_err());
+    }
+
+    #[test]
+    fn electrum_target_check_availability() {
+        // Expectency: A sane tip height means available, notifications are skipped.
+        let notification = String::from(
+            "{\"jsonrpc\":\"2.0\",\"method\":\"blockchain.headers.subscribe\",\"params\":[]}\n",
+        );
+        let (addr, server) = fake_server(vec![
+            version_response(),
+            notification + &header_response(840_000),
+        ]);
+        let target = ElectrumTarget::from(addr);
+        assert_eq!(target.get_server_info(), None);
+        assert_eq!(target.check_availability().unwrap(), Status::Available);
+        assert_eq!(
+            target.get_server_info(),
+            Some(ElectrumServerInfo {
+                server_software: String::from("Fulcrum 1.10.0"),
+                protocol_version: String::from("1.4"),
+                tip_height: 840_000,
+            })
+        );
+        assert_eq!(
+            server.join().unwrap(),
+            vec!["server.version", "blockchain.headers.subscribe"]
+        );
+    }
+
+    #[test]
+    fn electrum_target_check_insane_tip() {
+        // Expectency: Servers answering with errors or insane chain tips are not available.
+        let (addr, server) = fake_server(vec![version_response(), header_response(0)]);
+        let target = ElectrumTarget::from(addr);
+        assert_eq!(target.check_availability().unwrap(), Status::NotAvailable);
+        assert_eq!(target.get_server_info(), None);
+        server.join().unwrap();
+
+        let error = String::from(
+            "{\"jsonrpc\":\"2.0\",\"id\":0,\"error\":{\"code\":1,\"message\":\"unsupported\"}}\n",
+        );
+        let (addr, server) = fake_server(vec![error]);
+        let target = ElectrumTarget::from(addr);
+        assert_eq!(target.check_availability().unwrap(), Status::NotAvailable);
+        assert_eq!(server.join().unwrap(), vec!["server.version"]);
+    }
+
+    #[test]
+    fn electrum_target_check_unavailability() {
+        // Expectency: Closed ports and silent servers are not available.
+        let target = ElectrumTarget::from(closed_addr());
+        assert_eq!(target.check_availability().unwrap(), Status::NotAvailable);
+
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let target = ElectrumTarget::from(listener.local_addr().unwrap())
+            .set_connect_timeout(Duration::from_millis(100));
+        assert_eq!(target.check_availability().unwrap(), Status::NotAvailable);
+    }
+
+    #[test]
+    #[cfg(feature = "electrum-tls")]
+    fn electrum_target_check_tls_unavailability() {
+        // Expectency: Failed TLS handshakes are not available.
+        let (addr, server) = fake_server(vec![version_response(), header_response(840_000)]);
+        let target = ElectrumTarget::from(addr)
+            .set_transport(ElectrumTransport::Ssl)
+            .set_accept_invalid_certs(true)
+            .set_connect_timeout(Duration::from_millis(100));
+        assert_eq!(target.check_availability().unwrap(), Status::NotAvailable);
+        assert!(server.join().unwrap().is_empty());
+    }
+
+    #[test]
+    #[cfg(not(feature = "electrum-tls"))]
+    fn electrum_target_check_tls_unsupported() {
+        // Expectency: Without TLS support, checks via TLS are reported as error.
+        let target = ElectrumTarget::from(closed_addr()).set_transport(ElectrumTransport::Ssl);
+        assert!(target.check_availability().is_err());
+    }
+
+    #[test]
+    fn tip_height_parsing() {
+        // Expectency: Only positive heights with a complete header are sane.
+        let hex = "00".repeat(80);
+        assert_eq!(
+            parse_tip_height(&json!({"hex": hex, "height": 1})).unwrap(),
+            1
+        );
+        assert!(parse_tip_height(&json!({"hex": hex})).is_err());
+        assert!(parse_tip_height(&json!({"hex": "00", "height": 1})).is_err());
+        assert!(parse_tip_height(&json!({"hex": "zz".repeat(80), "height": 1})).is_err());
+        assert!(parse_tip_height(&Value::Null).is_err());
+    }
+}
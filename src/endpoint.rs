@@ -0,0 +1,531 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Module containing the endpoints of the mempool.space REST API shared by the blocking and
+//! the asynchronous client.
+//!
+//! # Notes
+//! Each [Endpoint] bundles the request path with the decoding of the response body, leaving
+//! only the I/O to the clients.
+
+// Imports
+use super::address::{Address, Utxo};
+use super::block::{Block, BlockStatus};
+use super::difficulty::DifficultyAdjustment;
+use super::error::{MempoolError, RejectReason};
+use super::fees::{MempoolBlock, RecommendedFees};
+use super::lightning::{
+    Channel, LatestNetworkStats, LightningNode, LightningSearch, NodeRanking, TopNode,
+};
+use super::mempool::{MempoolInfo, RecentTransaction};
+use super::mining::{
+    DifficultyEntry, Hashrate, Interval, MiningPools, PoolDetails, PoolHashrate, RewardStats,
+};
+use super::prices::{Currency, HistoricalPrice, Prices};
+use super::rate_limit::parse_retry_after;
+use super::transaction::{MerkleProof, Outspend, Transaction, TxStatus};
+use super::transport::{HttpMethod, HttpRequest, HttpResponse};
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+
+/// Alias for a function decoding a response body
+type Decoder<T> = fn(Vec<u8>) -> Result<T, MempoolError>;
+
+/// Request of a single API endpoint together with the decoding of its response body.
+pub(crate) struct Endpoint<T> {
+    /// [HttpMethod] of the request.
+    method: HttpMethod,
+    /// Path of the endpoint appended to the base url, e.g. "/blocks/tip/height".
+    path: String,
+    /// Text body sent with the request, empty if none.
+    body: String,
+    /// Function decoding the response body.
+    decode: Decoder<T>,
+}
+
+impl<T> Endpoint<T> {
+    /// Construct a GET [Endpoint] on given path.
+    fn get(path: String, decode: Decoder<T>) -> Self {
+        Endpoint {
+            method: HttpMethod::Get,
+            path,
+            body: String::new(),
+            decode,
+        }
+    }
+
+    /// Construct a POST [Endpoint] on given path sending given text body.
+    fn post(path: String, body: &str, decode: Decoder<T>) -> Self {
+        Endpoint {
+            method: HttpMethod::Post,
+            path,
+            body: String::from(body),
+            decode,
+        }
+    }
+
+    /// Get the [HttpMethod] of the endpoint.
+    pub(crate) fn get_method(&self) -> HttpMethod {
+        self.method
+    }
+
+    /// Get a reference to the path of the endpoint.
+    pub(crate) fn get_path(&self) -> &str {
+        &self.path
+    }
+
+    /// Construct the request on this endpoint of given base url.
+    ///
+    /// # Arguments
+    /// * base_url: url of the API to query, e.g. "https://mempool.space/api".
+    /// * timeout: [Duration] used as timeout for the whole request.
+    /// * user_agent: user agent sent with the request.
+    pub(crate) fn request(
+        &self,
+        base_url: &str,
+        timeout: Duration,
+        user_agent: &str,
+    ) -> HttpRequest {
+        let request = HttpRequest::new(self.method, &format!("{}{}", base_url, self.path))
+            .set_timeout(timeout)
+            .set_header("User-Agent", user_agent);
+        if self.body.is_empty() {
+            request
+        } else {
+            request
+                .set_header("Content-Type", "text/plain")
+                .set_body(self.body.as_bytes().to_vec())
+        }
+    }
+
+    /// Decode given response body of this endpoint.
+    pub(crate) fn decode(&self, body: Vec<u8>) -> Result<T, MempoolError> {
+        (self.decode)(body)
+    }
+}
+
+/// `GET /blocks/tip/height`
+pub(crate) fn block_tip_height() -> Endpoint<u64> {
+    Endpoint::get(String::from("/blocks/tip/height"), decode_number)
+}
+
+/// `GET /blocks/tip/hash`
+pub(crate) fn block_tip_hash() -> Endpoint<String> {
+    Endpoint::get(String::from("/blocks/tip/hash"), decode_text)
+}
+
+/// `GET /block/:hash`
+pub(crate) fn block(hash: &str) -> Endpoint<Block> {
+    Endpoint::get(format!("/block/{}", hash), decode_json)
+}
+
+/// `GET /block/:hash/header`
+pub(crate) fn block_header(hash: &str) -> Endpoint<String> {
+    Endpoint::get(format!("/block/{}/header", hash), decode_text)
+}
+
+/// `GET /block/:hash/status`
+pub(crate) fn block_status(hash: &str) -> Endpoint<BlockStatus> {
+    Endpoint::get(format!("/block/{}/status", hash), decode_json)
+}
+
+/// `GET /block/:hash/txids`
+pub(crate) fn block_txids(hash: &str) -> Endpoint<Vec<String>> {
+    Endpoint::get(format!("/block/{}/txids", hash), decode_json)
+}
+
+/// `GET /block/:hash/txs/:start_index`
+pub(crate) fn block_transactions(hash: &str, start_index: u32) -> Endpoint<Vec<Transaction>> {
+    Endpoint::get(format!("/block/{}/txs/{}", hash, start_index), decode_json)
+}
+
+/// `GET /block/:hash/raw`
+pub(crate) fn block_raw(hash: &str) -> Endpoint<Vec<u8>> {
+    Endpoint::get(format!("/block/{}/raw", hash), decode_bytes)
+}
+
+/// `GET /block-height/:height`
+pub(crate) fn block_hash(height: u64) -> Endpoint<String> {
+    Endpoint::get(format!("/block-height/{}", height), decode_text)
+}
+
+/// `GET /blocks/:start_height`, or `GET /blocks` without start height.
+pub(crate) fn blocks(start_height: Option<u64>) -> Endpoint<Vec<Block>> {
+    match start_height {
+        Some(start_height) => Endpoint::get(format!("/blocks/{}", start_height), decode_json),
+        None => Endpoint::get(String::from("/blocks"), decode_json),
+    }
+}
+
+/// `GET /tx/:txid`
+pub(crate) fn transaction(txid: &str) -> Endpoint<Transaction> {
+    Endpoint::get(format!("/tx/{}", txid), decode_json)
+}
+
+/// `GET /tx/:txid/status`
+pub(crate) fn transaction_status(txid: &str) -> Endpoint<TxStatus> {
+    Endpoint::get(format!("/tx/{}/status", txid), decode_json)
+}
+
+/// `GET /tx/:txid/hex`
+pub(crate) fn transaction_hex(txid: &str) -> Endpoint<String> {
+    Endpoint::get(format!("/tx/{}/hex", txid), decode_text)
+}
+
+/// `GET /tx/:txid/raw`
+pub(crate) fn transaction_raw(txid: &str) -> Endpoint<Vec<u8>> {
+    Endpoint::get(format!("/tx/{}/raw", txid), decode_bytes)
+}
+
+/// `GET /tx/:txid/merkle-proof`
+pub(crate) fn transaction_merkle_proof(txid: &str) -> Endpoint<MerkleProof> {
+    Endpoint::get(format!("/tx/{}/merkle-proof", txid), decode_json)
+}
+
+/// `GET /tx/:txid/merkleblock-proof`
+pub(crate) fn transaction_merkleblock_proof(txid: &str) -> Endpoint<String> {
+    Endpoint::get(format!("/tx/{}/merkleblock-proof", txid), decode_text)
+}
+
+/// `GET /tx/:txid/outspend/:vout`
+pub(crate) fn transaction_outspend(txid: &str, vout: u32) -> Endpoint<Outspend> {
+    Endpoint::get(format!("/tx/{}/outspend/{}", txid, vout), decode_json)
+}
+
+/// `GET /tx/:txid/outspends`
+pub(crate) fn transaction_outspends(txid: &str) -> Endpoint<Vec<Outspend>> {
+    Endpoint::get(format!("/tx/{}/outspends", txid), decode_json)
+}
+
+/// `POST /tx` with the hex encoded transaction as body.
+///
+/// # Notes
+/// Map failures via [broadcast_error] to classify rejections of the node.
+pub(crate) fn broadcast(raw_hex: &str) -> Endpoint<String> {
+    Endpoint::post(String::from("/tx"), raw_hex.trim(), decode_text)
+}
+
+/// Get the path of an address, e.g. "/address/:address".
+pub(crate) fn address_path(address: &str) -> String {
+    format!("/address/{}", address)
+}
+
+/// Get the path of a scripthash, e.g. "/scripthash/:hash".
+pub(crate) fn scripthash_path(scripthash: &str) -> String {
+    format!("/scripthash/{}", scripthash)
+}
+
+/// `GET /address/:address` or `GET /scripthash/:hash`, depending on given path.
+pub(crate) fn summary(path: &str) -> Endpoint<Address> {
+    Endpoint::get(String::from(path), decode_json)
+}
+
+/// `GET :path/txs` of an address or scripthash path.
+pub(crate) fn transactions(path: &str) -> Endpoint<Vec<Transaction>> {
+    Endpoint::get(format!("{}/txs", path), decode_json)
+}
+
+/// `GET :path/txs/chain/:last_seen_txid` of an address or scripthash path, or
+/// `GET :path/txs/chain` for the first page.
+pub(crate) fn transactions_chain(
+    path: &str,
+    last_seen_txid: Option<&str>,
+) -> Endpoint<Vec<Transaction>> {
+    match last_seen_txid {
+        Some(txid) => Endpoint::get(format!("{}/txs/chain/{}", path, txid), decode_json),
+        None => Endpoint::get(format!("{}/txs/chain", path), decode_json),
+    }
+}
+
+/// `GET :path/txs/mempool` of an address or scripthash path.
+pub(crate) fn transactions_mempool(path: &str) -> Endpoint<Vec<Transaction>> {
+    Endpoint::get(format!("{}/txs/mempool", path), decode_json)
+}
+
+/// `GET :path/utxo` of an address or scripthash path.
+pub(crate) fn utxos(path: &str) -> Endpoint<Vec<Utxo>> {
+    Endpoint::get(format!("{}/utxo", path), decode_json)
+}
+
+/// `GET /mempool`
+pub(crate) fn mempool() -> Endpoint<MempoolInfo> {
+    Endpoint::get(String::from("/mempool"), decode_json)
+}
+
+/// `GET /mempool/txids`
+pub(crate) fn mempool_txids() -> Endpoint<Vec<String>> {
+    Endpoint::get(String::from("/mempool/txids"), decode_json)
+}
+
+/// `GET /mempool/recent`
+pub(crate) fn mempool_recent() -> Endpoint<Vec<RecentTransaction>> {
+    Endpoint::get(String::from("/mempool/recent"), decode_json)
+}
+
+/// `GET /v1/fees/recommended`
+pub(crate) fn recommended_fees() -> Endpoint<RecommendedFees> {
+    Endpoint::get(String::from("/v1/fees/recommended"), decode_json)
+}
+
+/// `GET /v1/fees/mempool-blocks`
+pub(crate) fn mempool_blocks() -> Endpoint<Vec<MempoolBlock>> {
+    Endpoint::get(String::from("/v1/fees/mempool-blocks"), decode_json)
+}
+
+/// `GET /v1/difficulty-adjustment`
+pub(crate) fn difficulty_adjustment() -> Endpoint<DifficultyAdjustment> {
+    Endpoint::get(String::from("/v1/difficulty-adjustment"), decode_json)
+}
+
+/// `GET /v1/mining/pools/:interval`
+pub(crate) fn mining_pools(interval: Interval) -> Endpoint<MiningPools> {
+    Endpoint::get(format!("/v1/mining/pools/{}", interval), decode_json)
+}
+
+/// `GET /v1/mining/pool/:slug`
+pub(crate) fn mining_pool(slug: &str) -> Endpoint<PoolDetails> {
+    Endpoint::get(format!("/v1/mining/pool/{}", slug), decode_json)
+}
+
+/// `GET /v1/mining/pool/:slug/hashrate`
+pub(crate) fn mining_pool_hashrate(slug: &str) -> Endpoint<Vec<PoolHashrate>> {
+    Endpoint::get(format!("/v1/mining/pool/{}/hashrate", slug), decode_json)
+}
+
+/// `GET /v1/mining/pool/:slug/blocks/:height`, or `GET /v1/mining/pool/:slug/blocks`
+/// without height.
+pub(crate) fn mining_pool_blocks(slug: &str, before_height: Option<u64>) -> Endpoint<Vec<Block>> {
+    match before_height {
+        Some(height) => Endpoint::get(
+            format!("/v1/mining/pool/{}/blocks/{}", slug, height),
+            decode_json,
+        ),
+        None => Endpoint::get(format!("/v1/mining/pool/{}/blocks", slug), decode_json),
+    }
+}
+
+/// `GET /v1/mining/hashrate/:interval`
+pub(crate) fn hashrate(interval: Interval) -> Endpoint<Hashrate> {
+    Endpoint::get(format!("/v1/mining/hashrate/{}", interval), decode_json)
+}
+
+/// `GET /v1/mining/difficulty-adjustments/:interval`, or
+/// `GET /v1/mining/difficulty-adjustments` without interval.
+pub(crate) fn difficulty_adjustments(interval: Option<Interval>) -> Endpoint<Vec<DifficultyEntry>> {
+    match interval {
+        Some(interval) => Endpoint::get(
+            format!("/v1/mining/difficulty-adjustments/{}", interval),
+            decode_json,
+        ),
+        None => Endpoint::get(
+            String::from("/v1/mining/difficulty-adjustments"),
+            decode_json,
+        ),
+    }
+}
+
+/// `GET /v1/mining/reward-stats/:blockCount`
+pub(crate) fn reward_stats(block_count: u32) -> Endpoint<RewardStats> {
+    Endpoint::get(
+        format!("/v1/mining/reward-stats/{}", block_count),
+        decode_json,
+    )
+}
+
+/// `GET /v1/lightning/statistics/latest`
+pub(crate) fn lightning_statistics() -> Endpoint<LatestNetworkStats> {
+    Endpoint::get(String::from("/v1/lightning/statistics/latest"), decode_json)
+}
+
+/// `GET /v1/lightning/search?searchText=:query`
+pub(crate) fn search_lightning(query: &str) -> Endpoint<LightningSearch> {
+    Endpoint::get(
+        format!("/v1/lightning/search?searchText={}", encode_query(query)),
+        decode_json,
+    )
+}
+
+/// `GET /v1/lightning/nodes/:public_key`
+pub(crate) fn lightning_node(public_key: &str) -> Endpoint<LightningNode> {
+    Endpoint::get(format!("/v1/lightning/nodes/{}", public_key), decode_json)
+}
+
+/// `GET /v1/lightning/channels/:short_id`
+pub(crate) fn lightning_channel(short_id: &str) -> Endpoint<Channel> {
+    Endpoint::get(format!("/v1/lightning/channels/{}", short_id), decode_json)
+}
+
+/// `GET /v1/lightning/nodes/rankings/:ranking`
+pub(crate) fn lightning_top_nodes(ranking: NodeRanking) -> Endpoint<Vec<TopNode>> {
+    Endpoint::get(
+        format!("/v1/lightning/nodes/rankings/{}", ranking),
+        decode_json,
+    )
+}
+
+/// `GET /v1/prices`
+pub(crate) fn prices() -> Endpoint<Prices> {
+    Endpoint::get(String::from("/v1/prices"), decode_json)
+}
+
+/// `GET /v1/historical-price?currency=:currency&timestamp=:timestamp`
+pub(crate) fn historical_price(currency: Currency, timestamp: u64) -> Endpoint<HistoricalPrice> {
+    Endpoint::get(
+        format!(
+            "/v1/historical-price?currency={}&timestamp={}",
+            currency, timestamp
+        ),
+        decode_json,
+    )
+}
+
+/// Classify a failed broadcast. A 400 response carries the rejection message of the node and
+/// is mapped onto [MempoolError::Rejected], all other errors are returned unchanged.
+pub(crate) fn broadcast_error(error: MempoolError) -> MempoolError {
+    match error {
+        MempoolError::HttpStatus(400, message) => {
+            MempoolError::Rejected(RejectReason::from_message(&message), message)
+        }
+        error => error,
+    }
+}
+
+/// Map non-success status codes of given response onto a [MempoolError].
+pub(crate) fn check_status(response: HttpResponse) -> Result<HttpResponse, MempoolError> {
+    if response.is_success() {
+        Ok(response)
+    } else {
+        let status = response.get_status();
+        let retry_after = response.get_header("Retry-After").map(String::from);
+        let body = String::from_utf8_lossy(&response.into_body()).into_owned();
+        Err(status_error(status, retry_after.as_deref(), body))
+    }
+}
+
+/// Construct the [MempoolError] describing a non-success response.
+///
+/// # Arguments
+/// * status: the non-success status code of the response.
+/// * retry_after: value of the Retry-After header of the response, if any.
+/// * body: the response body, usually containing the servers error description.
+///
+/// # Notes
+/// Both 429 (Too Many Requests) and 503 (Service Unavailable) are reported as
+/// [MempoolError::RateLimited], as mempool.space answers with either when throttling.
+fn status_error(status: u16, retry_after: Option<&str>, body: String) -> MempoolError {
+    if status == 429 || status == 503 {
+        let retry_after = retry_after.and_then(parse_retry_after);
+        MempoolError::RateLimited { retry_after }
+    } else {
+        MempoolError::HttpStatus(status, body)
+    }
+}
+
+/// Percent encode given text for use as query parameter value.
+fn encode_query(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                String::from(byte as char)
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Deserialize a JSON response body.
+fn decode_json<T: DeserializeOwned>(body: Vec<u8>) -> Result<T, MempoolError> {
+    Ok(serde_json::from_slice(&body)?)
+}
+
+/// Decode a text response body, stripping surrounding whitespace.
+fn decode_text(body: Vec<u8>) -> Result<String, MempoolError> {
+    Ok(String::from(String::from_utf8(body)?.trim()))
+}
+
+/// Decode a response body containing a single number.
+fn decode_number(body: Vec<u8>) -> Result<u64, MempoolError> {
+    Ok(decode_text(body)?.parse()?)
+}
+
+/// Return a binary response body unchanged.
+fn decode_bytes(body: Vec<u8>) -> Result<Vec<u8>, MempoolError> {
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoint_request() {
+        // Expectency: Requests carry timeout and user agent, bodies are sent as plain text.
+        let request = block_tip_height().request("http://mock/api", Duration::from_secs(1), "a");
+        assert_eq!(request.get_method(), HttpMethod::Get);
+        assert_eq!(request.get_url(), "http://mock/api/blocks/tip/height");
+        assert_eq!(request.get_timeout(), Some(&Duration::from_secs(1)));
+        assert_eq!(request.get_headers().len(), 1);
+        assert!(request.get_body().is_empty());
+
+        let request = broadcast(" 0100\n").request("http://mock", Duration::from_secs(1), "a");
+        assert_eq!(request.get_method(), HttpMethod::Post);
+        assert_eq!(request.get_url(), "http://mock/tx");
+        assert!(request
+            .get_headers()
+            .contains(&(String::from("Content-Type"), String::from("text/plain"))));
+        assert_eq!(request.get_body(), b"0100");
+    }
+
+    #[test]
+    fn endpoint_paths() {
+        // Expectency: Optional path segments are only appended if given.
+        assert_eq!(blocks(None).get_path(), "/blocks");
+        assert_eq!(blocks(Some(840000)).get_path(), "/blocks/840000");
+        let path = address_path("bc1q");
+        assert_eq!(
+            transactions_chain(&path, None).get_path(),
+            "/address/bc1q/txs/chain"
+        );
+        assert_eq!(
+            transactions_chain(&scripthash_path("ab"), Some("a2")).get_path(),
+            "/scripthash/ab/txs/chain/a2"
+        );
+        assert_eq!(
+            search_lightning("ACINQ node").get_path(),
+            "/v1/lightning/search?searchText=ACINQ%20node"
+        );
+    }
+
+    #[test]
+    fn endpoint_decode() {
+        // Expectency: Text responses are trimmed, numbers parsed and malformed bodies reported.
+        let hash = "000000000000000000026e2a6b5b2ad1ef0d9e25d3c4d8e2b0f5a3f1e1c7d9b2";
+        let body = format!("{}\n", hash).into_bytes();
+        assert_eq!(block_tip_hash().decode(body).unwrap(), hash);
+        assert_eq!(
+            block_tip_height().decode(b" 840000\n".to_vec()).unwrap(),
+            840000
+        );
+        assert_eq!(block_raw("h").decode(vec![0, 1]).unwrap(), vec![0, 1]);
+        assert!(matches!(
+            mempool().decode(b"<html>".to_vec()),
+            Err(MempoolError::Decode(_, _))
+        ));
+    }
+
+    #[test]
+    fn endpoint_broadcast_error() {
+        // Expectency: Only 400 responses are classified as rejection.
+        let error = MempoolError::HttpStatus(400, String::from("bad-txns-inputs-missingorspent"));
+        assert!(matches!(
+            broadcast_error(error),
+            MempoolError::Rejected(RejectReason::InputsMissingOrSpent, _)
+        ));
+        let error = MempoolError::HttpStatus(500, String::new());
+        assert!(matches!(
+            broadcast_error(error),
+            MempoolError::HttpStatus(500, _)
+        ));
+    }
+}
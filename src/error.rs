@@ -11,10 +11,12 @@ use std::error::Error;
 use std::fmt::{self};
 use std::io::{self};
 use std::num::{self};
+use std::string::{self};
+use std::time::Duration;
 
 // Documentation imports
 #[cfg(doc)]
-use super::Target;
+use super::{Network, Target};
 
 /// Alias for preallocated error messages
 pub type ErrorMessage = &'static str;
@@ -217,6 +219,199 @@ impl From<Box<dyn Error>> for CheckTargetError {
     }
 }
 
+/// Custom error type for a failed attempt to parse an API parameter like a [Network].
+#[derive(Debug)]
+pub enum ParseParameterError {
+    /// ParseParameterError containing a Message
+    Message(ErrorMessage),
+}
+
+impl Error for ParseParameterError {}
+
+impl fmt::Display for ParseParameterError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseParameterError::Message(error_message) => write!(formatter, "{}", error_message),
+        }
+    }
+}
+
+impl From<ErrorMessage> for ParseParameterError {
+    fn from(message: ErrorMessage) -> Self {
+        ParseParameterError::Message(message)
+    }
+}
+
+/// Alias for a thread safe trait object implementing [Error]
+pub type BoxedError = Box<dyn Error + Send + Sync>;
+
+/// Custom error type for a failed request against the mempool.space API.
+#[derive(Debug)]
+pub enum MempoolError {
+    /// MempoolError containing a Message and the error of the underlying HTTP transport
+    Transport(ErrorMessage, BoxedError),
+    /// MempoolError containing the non-success HTTP status code and the response body
+    HttpStatus(u16, String),
+    /// MempoolError containing a Message and the error raised while decoding a response body
+    Decode(ErrorMessage, BoxedError),
+    /// MempoolError signaling the server rejected a request due to rate limiting. Contains
+    /// the [Duration] to wait before retrying, if the server announced one.
+    RateLimited {
+        /// [Duration] to wait until the next request is allowed
+        retry_after: Option<Duration>,
+    },
+    /// MempoolError signaling a broadcast transaction was rejected. Contains the classified
+    /// [RejectReason] and the rejection message of the server.
+    Rejected(RejectReason, String),
+}
+
+impl Error for MempoolError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MempoolError::Transport(_, ref error) | MempoolError::Decode(_, ref error) => {
+                Some(error.as_ref())
+            }
+            MempoolError::HttpStatus(_, _)
+            | MempoolError::RateLimited { .. }
+            | MempoolError::Rejected(_, _) => None,
+        }
+    }
+}
+
+impl fmt::Display for MempoolError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MempoolError::Transport(error_message, error)
+            | MempoolError::Decode(error_message, error) => {
+                write!(formatter, "{} caused by: {}", error_message, error)
+            }
+            MempoolError::HttpStatus(status, body) if body.is_empty() => {
+                write!(formatter, "HTTP status {}", status)
+            }
+            MempoolError::HttpStatus(status, body) => {
+                write!(formatter, "HTTP status {}: {}", status, body)
+            }
+            MempoolError::RateLimited {
+                retry_after: Some(retry_after),
+            } => write!(
+                formatter,
+                "Rate limited, retry after {}s",
+                retry_after.as_secs()
+            ),
+            MempoolError::RateLimited { retry_after: None } => write!(formatter, "Rate limited"),
+            MempoolError::Rejected(_, message) => {
+                write!(formatter, "Transaction rejected: {}", message)
+            }
+        }
+    }
+}
+
+/// Reason a node rejected a broadcast transaction, classified from its rejection message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RejectReason {
+    /// The feerate is below the minimum relay feerate of the node
+    MinRelayFeeNotMet,
+    /// The feerate is below the dynamic minimum feerate of the full mempool
+    MempoolMinFeeNotMet,
+    /// The fee is too low, e.g. to replace a conflicting transaction
+    InsufficientFee,
+    /// At least one input is unknown or already spent
+    InputsMissingOrSpent,
+    /// At least one input is spent by a transaction in the mempool
+    MempoolConflict,
+    /// The transaction is already in the mempool or the block chain
+    AlreadyKnown,
+    /// At least one output is below the dust threshold
+    Dust,
+    /// The transaction is not final, e.g. due to its locktime
+    NonFinal,
+    /// The transaction could not be deserialized
+    DecodeFailed,
+    /// Any other rejection
+    Other,
+}
+
+impl RejectReason {
+    /// Classify the rejection message of a node, e.g. "min relay fee not met, 100 < 141".
+    ///
+    /// # Example
+    /// ```
+    /// # use mempool_space::error::RejectReason;
+    ///
+    /// let message = r#"sendrawtransaction RPC error: {"code":-25,"message":"bad-txns-inputs-missingorspent"}"#;
+    /// assert_eq!(RejectReason::from_message(message), RejectReason::InputsMissingOrSpent);
+    /// ```
+    pub fn from_message(message: &str) -> Self {
+        let message = message.to_lowercase();
+        let contains_any = |patterns: &[&str]| patterns.iter().any(|p| message.contains(p));
+
+        if contains_any(&["min relay fee not met"]) {
+            RejectReason::MinRelayFeeNotMet
+        } else if contains_any(&["mempool min fee not met"]) {
+            RejectReason::MempoolMinFeeNotMet
+        } else if contains_any(&["insufficient fee"]) {
+            RejectReason::InsufficientFee
+        } else if contains_any(&["missingorspent", "missing-inputs", "missing inputs"]) {
+            RejectReason::InputsMissingOrSpent
+        } else if contains_any(&["txn-mempool-conflict"]) {
+            RejectReason::MempoolConflict
+        } else if contains_any(&["txn-already", "already in block chain"]) {
+            RejectReason::AlreadyKnown
+        } else if contains_any(&["dust"]) {
+            RejectReason::Dust
+        } else if contains_any(&["non-final", "non-bip68-final"]) {
+            RejectReason::NonFinal
+        } else if contains_any(&["decode failed"]) {
+            RejectReason::DecodeFailed
+        } else {
+            RejectReason::Other
+        }
+    }
+}
+
+#[cfg(feature = "transport-reqwest")]
+impl From<reqwest::Error> for MempoolError {
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_decode() {
+            MempoolError::Decode("Failed to decode response", Box::new(error))
+        } else {
+            MempoolError::Transport("Failed to perform request", Box::new(error))
+        }
+    }
+}
+
+#[cfg(feature = "transport-ureq")]
+impl From<ureq::Error> for MempoolError {
+    fn from(error: ureq::Error) -> Self {
+        MempoolError::Transport("Failed to perform request", Box::new(error))
+    }
+}
+
+#[cfg(feature = "websocket")]
+impl From<tokio_tungstenite::tungstenite::Error> for MempoolError {
+    fn from(error: tokio_tungstenite::tungstenite::Error) -> Self {
+        MempoolError::Transport("Failed to use WebSocket connection", Box::new(error))
+    }
+}
+
+impl From<serde_json::Error> for MempoolError {
+    fn from(error: serde_json::Error) -> Self {
+        MempoolError::Decode("Failed to decode JSON response", Box::new(error))
+    }
+}
+
+impl From<string::FromUtf8Error> for MempoolError {
+    fn from(error: string::FromUtf8Error) -> Self {
+        MempoolError::Decode("Failed to decode text response", Box::new(error))
+    }
+}
+
+impl From<num::ParseIntError> for MempoolError {
+    fn from(error: num::ParseIntError) -> Self {
+        MempoolError::Decode("Failed to decode integer response", Box::new(error))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -352,4 +547,109 @@ mod tests {
             "ResolveTargetError caused by: IoError caused by: timed out"
         );
     }
+
+    // ParseParameterError tests
+    #[test]
+    fn parse_parameter_error_from_str() {
+        // Expectency: A ParseParameterError must contain its error message.
+        assert_eq!(
+            format!("{}", ParseParameterError::from("Error Message!")),
+            "Error Message!"
+        );
+    }
+
+    // MempoolError tests
+    #[test]
+    fn mempool_error_from_parse_int_error() {
+        // Expectency: A MempoolError must contain its error message and the description
+        //             of the inner ParseIntError.
+        let error = "<html>".parse::<u64>().unwrap_err();
+        assert_eq!(
+            format!("{}", MempoolError::from(error)),
+            "Failed to decode integer response caused by: invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn mempool_error_http_status() {
+        // Expectency: A MempoolError must contain the status code and the response body if any.
+        assert_eq!(
+            format!(
+                "{}",
+                MempoolError::HttpStatus(404, String::from("Block not found"))
+            ),
+            "HTTP status 404: Block not found"
+        );
+        assert_eq!(
+            format!("{}", MempoolError::HttpStatus(500, String::new())),
+            "HTTP status 500"
+        );
+    }
+
+    #[test]
+    fn mempool_error_rate_limited() {
+        // Expectency: A rate limited MempoolError must contain the announced retry duration.
+        let error = MempoolError::RateLimited {
+            retry_after: Some(Duration::from_secs(30)),
+        };
+        assert_eq!(format!("{}", error), "Rate limited, retry after 30s");
+        assert!(error.source().is_none());
+
+        let error = MempoolError::RateLimited { retry_after: None };
+        assert_eq!(format!("{}", error), "Rate limited");
+    }
+
+    #[test]
+    fn mempool_error_rejected() {
+        // Expectency: A rejected MempoolError must contain the rejection message.
+        let message = String::from("min relay fee not met, 100 < 141");
+        let error = MempoolError::Rejected(RejectReason::from_message(&message), message);
+        assert!(matches!(
+            error,
+            MempoolError::Rejected(RejectReason::MinRelayFeeNotMet, _)
+        ));
+        assert_eq!(
+            format!("{}", error),
+            "Transaction rejected: min relay fee not met, 100 < 141"
+        );
+    }
+
+    // RejectReason tests
+    #[test]
+    fn reject_reason_from_message() {
+        // Expectency: Known rejection messages of bitcoind must be classified,
+        //             unknown messages must be classified as Other.
+        let cases = [
+            (
+                "min relay fee not met, 100 < 141",
+                RejectReason::MinRelayFeeNotMet,
+            ),
+            (
+                "mempool min fee not met, 141 < 2000",
+                RejectReason::MempoolMinFeeNotMet,
+            ),
+            (
+                "insufficient fee, rejecting replacement",
+                RejectReason::InsufficientFee,
+            ),
+            (
+                "bad-txns-inputs-missingorspent",
+                RejectReason::InputsMissingOrSpent,
+            ),
+            ("missing-inputs", RejectReason::InputsMissingOrSpent),
+            ("txn-mempool-conflict", RejectReason::MempoolConflict),
+            (
+                "Transaction already in block chain",
+                RejectReason::AlreadyKnown,
+            ),
+            ("txn-already-known", RejectReason::AlreadyKnown),
+            ("dust", RejectReason::Dust),
+            ("non-final", RejectReason::NonFinal),
+            ("TX decode failed", RejectReason::DecodeFailed),
+            ("scriptpubkey", RejectReason::Other),
+        ];
+        for (message, reason) in cases {
+            assert_eq!(RejectReason::from_message(message), reason, "{}", message);
+        }
+    }
 }
@@ -0,0 +1,316 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Module containing failover between multiple mempool backends.
+
+// Imports
+use super::endpoint::{self, check_status};
+use super::error::{MempoolError, ParseTargetError};
+use super::target::{tcp_target_from_url, Status, Target, TcpTarget};
+#[cfg(feature = "async")]
+use super::transport::AsyncHttpTransport;
+use super::transport::HttpTransport;
+use std::cmp::Reverse;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// Documentation imports
+#[cfg(doc)]
+use super::MempoolClient;
+
+/// Default [Duration] between two availability checks of the backends of a [MempoolClient]
+pub const DEFAULT_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Snapshot of the state of a single backend of a [MempoolClient].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Backend {
+    /// Base url of the backend, e.g. "https://mempool.space/api".
+    base_url: String,
+    /// [Status] of the last availability check or failed request.
+    status: Status,
+    /// Chain tip height reported during the last availability check, if any.
+    tip_height: Option<u64>,
+}
+
+impl Backend {
+    /// Get a reference to the base url of the backend.
+    pub fn get_base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Get a reference to the [Status] of the backend.
+    pub fn get_status(&self) -> &Status {
+        &self.status
+    }
+
+    /// Get the chain tip height reported by the backend, if known.
+    pub fn get_tip_height(&self) -> Option<u64> {
+        self.tip_height
+    }
+}
+
+/// Ordered set of backends shared by the requests of a client.
+///
+/// # Notes
+/// Availability checks run in the background of the request claiming them. Each check
+/// takes up to the connect timeout of the [TcpTarget] plus the request timeout per backend,
+/// meanwhile requests are routed by the results of the previous check.
+#[derive(Debug)]
+pub(crate) struct BackendPool {
+    /// [TcpTarget]s used for availability checks, one per backend.
+    targets: Vec<Arc<TcpTarget>>,
+    /// Current state of each backend, in order of preference.
+    backends: Mutex<Vec<Backend>>,
+    /// [Duration] between two availability checks.
+    check_interval: Duration,
+    /// Point in time of the last availability check, if any.
+    last_check: Mutex<Option<Instant>>,
+}
+
+impl BackendPool {
+    /// Construct a [BackendPool] with all backends in [Status::Unknown].
+    ///
+    /// # Arguments
+    /// * base_urls: base urls of all backends, in order of preference.
+    ///
+    /// # Returns
+    /// * On success, the [BackendPool] using [DEFAULT_CHECK_INTERVAL].
+    /// * On failure, a [ParseTargetError] if no base url was given or a base url is malformed.
+    pub(crate) fn new(base_urls: &[&str]) -> Result<Self, ParseTargetError> {
+        if base_urls.is_empty() {
+            return Err(ParseTargetError::from("No base url found"));
+        }
+        let targets = base_urls
+            .iter()
+            .map(|base_url| tcp_target_from_url(base_url).map(Arc::new))
+            .collect::<Result<_, _>>()?;
+        let backends = base_urls
+            .iter()
+            .map(|base_url| Backend {
+                base_url: String::from(base_url.trim_end_matches('/')),
+                status: Status::Unknown,
+                tip_height: None,
+            })
+            .collect();
+        Ok(BackendPool {
+            targets,
+            backends: Mutex::new(backends),
+            check_interval: DEFAULT_CHECK_INTERVAL,
+            last_check: Mutex::new(None),
+        })
+    }
+
+    /// Set a new [Duration] between two availability checks.
+    pub(crate) fn set_check_interval(&mut self, check_interval: Duration) {
+        self.check_interval = check_interval;
+    }
+
+    /// Get a reference to the [Duration] between two availability checks.
+    pub(crate) fn get_check_interval(&self) -> &Duration {
+        &self.check_interval
+    }
+
+    /// Get a snapshot of all backends.
+    pub(crate) fn get_backends(&self) -> Vec<Backend> {
+        self.backends.lock().unwrap().clone()
+    }
+
+    /// Claim the next availability check.
+    ///
+    /// # Returns
+    /// true once the check interval elapsed since the last check. Concurrent callers
+    /// receive false until the interval elapsed again.
+    pub(crate) fn claim_check(&self) -> bool {
+        let mut last_check = self.last_check.lock().unwrap();
+        match *last_check {
+            Some(instant) if instant.elapsed() < self.check_interval => false,
+            _ => {
+                *last_check = Some(Instant::now());
+                true
+            }
+        }
+    }
+
+    /// Check the availability of all backends and query the tip height of available ones.
+    ///
+    /// # Arguments
+    /// * transport: [HttpTransport] performing the tip height requests.
+    /// * timeout: timeout [Duration] of each tip height request.
+    /// * user_agent: user agent sent with each tip height request.
+    pub(crate) fn check(&self, transport: &dyn HttpTransport, timeout: Duration, user_agent: &str) {
+        for (index, (target, backend)) in self.targets.iter().zip(self.get_backends()).enumerate() {
+            let tip_height = match target.check_availability() {
+                Ok(Status::Available) => {
+                    let endpoint = endpoint::block_tip_height();
+                    let request = endpoint.request(backend.get_base_url(), timeout, user_agent);
+                    transport
+                        .send(&request)
+                        .and_then(check_status)
+                        .and_then(|response| endpoint.decode(response.into_body()))
+                        .ok()
+                }
+                _ => None,
+            };
+            self.update_checked(index, tip_height);
+        }
+    }
+
+    /// Check the availability of all backends like [BackendPool::check], without blocking.
+    /// The blocking [TcpTarget] checks are offloaded onto a separate thread.
+    #[cfg(feature = "async")]
+    pub(crate) async fn check_async(
+        &self,
+        transport: &dyn AsyncHttpTransport,
+        timeout: Duration,
+        user_agent: &str,
+    ) {
+        for (index, (target, backend)) in self.targets.iter().zip(self.get_backends()).enumerate() {
+            let target = target.clone();
+            let status = tokio::task::spawn_blocking(move || {
+                target.check_availability().unwrap_or(Status::Unknown)
+            })
+            .await;
+            let tip_height = match status {
+                Ok(Status::Available) => {
+                    let endpoint = endpoint::block_tip_height();
+                    let request = endpoint.request(backend.get_base_url(), timeout, user_agent);
+                    match transport.send(&request).await {
+                        Ok(response) => check_status(response)
+                            .and_then(|response| endpoint.decode(response.into_body()))
+                            .ok(),
+                        Err(_) => None,
+                    }
+                }
+                _ => None,
+            };
+            self.update_checked(index, tip_height);
+        }
+    }
+
+    /// Store the result of an availability check of given backend. Backends answering with
+    /// their tip height are available.
+    fn update_checked(&self, index: usize, tip_height: Option<u64>) {
+        match tip_height {
+            Some(tip_height) => self.update(index, Status::Available, Some(tip_height)),
+            None => self.update(index, Status::NotAvailable, None),
+        }
+    }
+
+    /// Store the result of an availability check of given backend.
+    pub(crate) fn update(&self, index: usize, status: Status, tip_height: Option<u64>) {
+        let mut backends = self.backends.lock().unwrap();
+        backends[index].status = status;
+        backends[index].tip_height = tip_height;
+    }
+
+    /// Mark given backend as [Status::NotAvailable] after a failed request.
+    pub(crate) fn mark_failed(&self, index: usize) {
+        self.backends.lock().unwrap()[index].status = Status::NotAvailable;
+    }
+
+    /// Get the order backends are tried in.
+    ///
+    /// # Returns
+    /// Index and base url of all backends. Available backends come first, those with the
+    /// highest tip height ahead, followed by unknown and not available backends as last
+    /// resort. Ties keep the order of preference.
+    pub(crate) fn route(&self) -> Vec<(usize, String)> {
+        let backends = self.backends.lock().unwrap();
+        let mut route: Vec<_> = backends.iter().enumerate().collect();
+        route.sort_by_key(|(_, backend)| {
+            let rank = match backend.status {
+                Status::Available => 0,
+                Status::Unknown => 1,
+                Status::NotAvailable => 2,
+            };
+            (rank, Reverse(backend.tip_height))
+        });
+        route
+            .into_iter()
+            .map(|(index, backend)| (index, backend.base_url.clone()))
+            .collect()
+    }
+}
+
+/// Check if a request failing with given error should be tried on the next backend.
+///
+/// # Returns
+/// true for transport errors, rate limiting and server side errors. Errors caused by the
+/// request itself, e.g. a rejected transaction, would fail on every backend.
+pub(crate) fn is_failover_error(error: &MempoolError) -> bool {
+    match error {
+        MempoolError::Transport(_, _) | MempoolError::RateLimited { .. } => true,
+        MempoolError::HttpStatus(status, _) => *status >= 500,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backend_pool_new() {
+        // Expectency: Targets are derived from the base urls, including default ports.
+        let pool =
+            BackendPool::new(&["https://mempool.space/api/", "http://[::1]:8999/api"]).unwrap();
+        let targets = &pool.targets;
+        assert_eq!(targets[0].get_fqhn(), "mempool.space");
+        assert_eq!(targets[0].get_portnumber(), &443);
+        assert_eq!(targets[1].get_fqhn(), "::1");
+        assert_eq!(targets[1].get_portnumber(), &8999);
+
+        let backends = pool.get_backends();
+        assert_eq!(backends[0].get_base_url(), "https://mempool.space/api");
+        assert_eq!(backends[1].get_status(), &Status::Unknown);
+        assert_eq!(pool.get_check_interval(), &DEFAULT_CHECK_INTERVAL);
+
+        assert!(BackendPool::new(&[]).is_err());
+        assert!(BackendPool::new(&["mempool.space/api"]).is_err());
+        assert!(BackendPool::new(&["ftp://mempool.space"]).is_err());
+        assert!(BackendPool::new(&["http://mempool.space:0/api"]).is_err());
+        assert!(BackendPool::new(&["http:///api"]).is_err());
+    }
+
+    #[test]
+    fn backend_pool_route() {
+        // Expectency: Available backends with the highest tip come first, failed backends last.
+        let pool = BackendPool::new(&["http://a", "http://b", "http://c", "http://d"]).unwrap();
+        pool.update(0, Status::Available, Some(100));
+        pool.update(1, Status::NotAvailable, None);
+        pool.update(3, Status::Available, Some(101));
+        let order: Vec<usize> = pool.route().into_iter().map(|(index, _)| index).collect();
+        assert_eq!(order, vec![3, 0, 2, 1]);
+
+        pool.mark_failed(3);
+        assert_eq!(pool.route()[0], (0, String::from("http://a")));
+    }
+
+    #[test]
+    fn backend_pool_claim_check() {
+        // Expectency: Checks are claimed once per check interval.
+        let mut pool = BackendPool::new(&["http://a"]).unwrap();
+        pool.set_check_interval(Duration::from_millis(50));
+        assert!(pool.claim_check());
+        assert!(!pool.claim_check());
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(pool.claim_check());
+    }
+
+    #[test]
+    fn failover_errors() {
+        // Expectency: Only errors specific to a backend lead to failover.
+        assert!(is_failover_error(&MempoolError::RateLimited {
+            retry_after: None
+        }));
+        assert!(is_failover_error(&MempoolError::HttpStatus(
+            502,
+            String::new()
+        )));
+        assert!(!is_failover_error(&MempoolError::HttpStatus(
+            404,
+            String::new()
+        )));
+    }
+}
@@ -0,0 +1,237 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Module containing types returned by the mempool.space fee endpoints.
+
+// Imports
+use serde::Deserialize;
+
+/// Maximum virtual size of a block
+const MAX_BLOCK_VSIZE: f64 = 1_000_000.0;
+
+/// Recommended feerates in sat/vB as returned by `GET /v1/fees/recommended`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecommendedFees {
+    /// Feerate to be included in the next block
+    pub fastest_fee: f64,
+    /// Feerate to be included within half an hour
+    pub half_hour_fee: f64,
+    /// Feerate to be included within an hour
+    pub hour_fee: f64,
+    /// Feerate for low priority transactions
+    pub economy_fee: f64,
+    /// Minimum feerate accepted into the mempool
+    pub minimum_fee: f64,
+}
+
+/// Projected block built from the current mempool as returned by `GET /v1/fees/mempool-blocks`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MempoolBlock {
+    /// Serialized size of the projected block in bytes
+    pub block_size: u64,
+    /// Virtual size of the projected block
+    #[serde(rename = "blockVSize")]
+    pub block_vsize: f64,
+    /// Number of transactions in the projected block
+    pub n_tx: u64,
+    /// Sum of all fees in the projected block in satoshis
+    pub total_fees: u64,
+    /// Median feerate of the projected block in sat/vB
+    pub median_fee: f64,
+    /// Feerate percentiles of the projected block in sat/vB, ordered from lowest to highest
+    pub fee_range: Vec<f64>,
+}
+
+/// Pick a feerate to confirm within the given number of blocks from projected mempool blocks.
+///
+/// # Arguments
+/// * mempool_blocks: projected blocks as returned by `GET /v1/fees/mempool-blocks`, next block first.
+/// * target_blocks: number of blocks the transaction should confirm in. 0 is treated as 1.
+/// * minimum_fee: lower bound of the returned feerate, usually [RecommendedFees::minimum_fee].
+///
+/// # Returns
+/// The median feerate of the projected block at the confirmation target in sat/vB. If the
+/// mempool is expected to clear before the target is reached, minimum_fee is returned.
+///
+/// # Notes
+/// The last projected block holds the whole remaining backlog and may exceed the maximum
+/// block size by far. Targets within such a block are answered from its fee range: the
+/// feerate that gets the remaining blocks up to the target filled from the highest feerates
+/// downwards.
+///
+/// # Example
+/// ```
+/// # use mempool_space::fees::{feerate_for_target, MempoolBlock};
+///
+/// let block = |median_fee| MempoolBlock {
+///     block_size: 1_500_000,
+///     block_vsize: 997_000.0,
+///     n_tx: 3000,
+///     total_fees: 10_000_000,
+///     median_fee,
+///     fee_range: vec![median_fee - 1.0, median_fee, median_fee + 5.0],
+/// };
+/// let mempool_blocks = vec![block(20.0), block(12.0), block(4.0)];
+///
+/// assert_eq!(feerate_for_target(&mempool_blocks, 2, 1.0), 12.0);
+/// assert_eq!(feerate_for_target(&mempool_blocks, 6, 1.0), 1.0);
+/// ```
+pub fn feerate_for_target(
+    mempool_blocks: &[MempoolBlock],
+    target_blocks: usize,
+    minimum_fee: f64,
+) -> f64 {
+    let index = target_blocks.max(1) - 1;
+    let last_index = match mempool_blocks.len().checked_sub(1) {
+        Some(last_index) => last_index,
+        None => return minimum_fee,
+    };
+    let last = &mempool_blocks[last_index];
+    if index < last_index || (index == last_index && last.block_vsize <= MAX_BLOCK_VSIZE) {
+        return mempool_blocks[index].median_fee.max(minimum_fee);
+    }
+
+    // Note: The backlog is mined highest feerates first, one maximum sized block at a time
+    let cleared_vsize = (index - last_index + 1) as f64 * MAX_BLOCK_VSIZE;
+    if cleared_vsize >= last.block_vsize {
+        return minimum_fee;
+    }
+    let fraction = 1.0 - cleared_vsize / last.block_vsize;
+    interpolate(&last.fee_range, fraction)
+        .unwrap_or(last.median_fee)
+        .max(minimum_fee)
+}
+
+/// Interpolate the feerate at given fraction, between 0 and 1, of an ordered fee range.
+///
+/// # Returns
+/// The interpolated feerate, None if the fee range is empty.
+fn interpolate(fee_range: &[f64], fraction: f64) -> Option<f64> {
+    let position = fraction.clamp(0.0, 1.0) * fee_range.len().checked_sub(1)? as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    let weight = position - lower as f64;
+    Some(fee_range[lower] + (fee_range[upper] - fee_range[lower]) * weight)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recommended_fees_deserialize() {
+        // Expectency: The camel case keys of the API must map onto RecommendedFees.
+        let json = r#"{
+            "fastestFee": 21,
+            "halfHourFee": 18,
+            "hourFee": 15,
+            "economyFee": 6,
+            "minimumFee": 3
+        }"#;
+        let fees: RecommendedFees = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            fees,
+            RecommendedFees {
+                fastest_fee: 21.0,
+                half_hour_fee: 18.0,
+                hour_fee: 15.0,
+                economy_fee: 6.0,
+                minimum_fee: 3.0,
+            }
+        );
+    }
+
+    fn mempool_blocks() -> Vec<MempoolBlock> {
+        let json = r#"[
+            {
+                "blockSize": 1325044,
+                "blockVSize": 997967.25,
+                "nTx": 2514,
+                "totalFees": 11614590,
+                "medianFee": 10.5,
+                "feeRange": [9.1, 9.9, 10.2, 10.5, 12.0, 15.0, 80.0]
+            },
+            {
+                "blockSize": 1641932,
+                "blockVSize": 997999.5,
+                "nTx": 3970,
+                "totalFees": 4518360,
+                "medianFee": 3.2,
+                "feeRange": [3.0, 3.0, 3.1, 3.2, 4.0, 5.0, 9.0]
+            },
+            {
+                "blockSize": 312084,
+                "blockVSize": 152088.0,
+                "nTx": 512,
+                "totalFees": 152088,
+                "medianFee": 0.5,
+                "feeRange": [0.1, 0.2, 0.5, 1.0, 2.0, 2.0, 3.0]
+            }
+        ]"#;
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn mempool_blocks_deserialize() {
+        // Expectency: The camel case keys of the API must map onto MempoolBlock.
+        let blocks = mempool_blocks();
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].block_vsize, 997967.25);
+        assert_eq!(blocks[0].n_tx, 2514);
+        assert_eq!(blocks[1].fee_range.len(), 7);
+    }
+
+    #[test]
+    fn feerate_for_target_within_projection() {
+        // Expectency: The median feerate of the projected block at the target is returned,
+        //             a target of 0 is treated like a target of 1.
+        let blocks = mempool_blocks();
+        assert_eq!(feerate_for_target(&blocks, 0, 1.0), 10.5);
+        assert_eq!(feerate_for_target(&blocks, 1, 1.0), 10.5);
+        assert_eq!(feerate_for_target(&blocks, 2, 1.0), 3.2);
+    }
+
+    #[test]
+    fn feerate_for_target_minimum_fee() {
+        // Expectency: The minimum fee is returned if the target lies behind the projection
+        //             or the projected median is lower than the minimum fee.
+        let blocks = mempool_blocks();
+        assert_eq!(feerate_for_target(&blocks, 3, 1.0), 1.0);
+        assert_eq!(feerate_for_target(&blocks, 10, 1.0), 1.0);
+        assert_eq!(feerate_for_target(&[], 1, 2.0), 2.0);
+    }
+
+    #[test]
+    fn feerate_for_target_overflowing_backlog() {
+        // Expectency: Targets within an overflowing last block are answered from its fee
+        //             range, the minimum fee only once the backlog is expected to clear.
+        let mut blocks = mempool_blocks();
+        blocks[2].block_vsize = 4_000_000.0;
+        blocks[2].fee_range = vec![2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 14.0];
+        blocks[2].median_fee = 5.0;
+
+        // Note: The last block covers 4 blocks, 1, 2 and 3 of them leave 3/4, 1/2 and 1/4
+        assert_eq!(feerate_for_target(&blocks, 3, 1.0), 6.5);
+        assert_eq!(feerate_for_target(&blocks, 4, 1.0), 5.0);
+        assert_eq!(feerate_for_target(&blocks, 5, 1.0), 3.5);
+        assert_eq!(feerate_for_target(&blocks, 5, 4.0), 4.0);
+        assert_eq!(feerate_for_target(&blocks, 6, 1.0), 1.0);
+        assert_eq!(feerate_for_target(&blocks, 10, 1.0), 1.0);
+
+        blocks[2].fee_range.clear();
+        assert_eq!(feerate_for_target(&blocks, 4, 1.0), 5.0);
+    }
+
+    #[test]
+    fn interpolate_fee_range() {
+        // Expectency: Feerates between two percentiles are interpolated linearly.
+        assert_eq!(interpolate(&[1.0, 3.0, 7.0], 0.0), Some(1.0));
+        assert_eq!(interpolate(&[1.0, 3.0, 7.0], 0.25), Some(2.0));
+        assert_eq!(interpolate(&[1.0, 3.0, 7.0], 1.0), Some(7.0));
+        assert_eq!(interpolate(&[4.0], 0.5), Some(4.0));
+        assert_eq!(interpolate(&[], 0.5), None);
+    }
+}
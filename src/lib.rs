@@ -12,26 +12,99 @@
 //!
 //! Additionally this crate contains asynchronous utilities to execute these checks regularly
 //! within a given time interval.
-use crate::blockheight::blockheight;
+//!
+//! The [MempoolClient] offers typed access to the mempool.space REST API. With feature "async"
+//! enabled, the AsyncMempoolClient offers the same endpoints without blocking.
+#![allow(clippy::suspicious_command_arg_space)]
+#![cfg_attr(test, allow(clippy::bool_assert_comparison, clippy::from_str_radix_10))]
+use crate::blockheight::{blockheight, blockheight_on};
 
 // Modules
+pub mod address;
+pub mod block;
 pub mod blockheight;
+pub mod cache;
+pub mod client;
+pub mod difficulty;
+pub mod electrum_target;
 pub mod error;
+pub mod failover;
+pub mod fees;
+pub mod lightning;
+pub mod mempool;
+pub mod mining;
+pub mod network;
+pub mod p2p_target;
+pub mod prices;
+pub mod rate_limit;
 pub mod resolve_policy;
+pub mod rpc_target;
 pub mod target;
-pub fn get_blockheight() -> Result<String, &'static str> {
-    let _blockheight_no_nl = blockheight().unwrap().to_string();
-
-    Ok(format!("{}", blockheight().unwrap().to_string()))
+pub mod transaction;
+pub mod transport;
+pub fn get_blockheight() -> Result<String, MempoolError> {
+    Ok(blockheight()?.to_string())
+}
+pub fn get_blockheight_on(network: &Network) -> Result<String, MempoolError> {
+    Ok(blockheight_on(network)?.to_string())
 }
 
+#[cfg(feature = "async")]
+pub mod async_client;
 #[cfg(feature = "async")]
 pub mod async_target;
 
+#[cfg(feature = "websocket")]
+pub mod stream;
+
+mod endpoint;
+#[cfg(test)]
+mod test_utils;
+
 // Re-exports
-pub use error::{CheckTargetError, ParseTargetError, ResolveTargetError};
+pub use address::{Address, AddressStats, Utxo};
+pub use block::{Block, BlockStatus};
+pub use cache::{CachePolicy, ResponseCache};
+pub use client::{MempoolClient, TransactionHistory};
+pub use difficulty::{DifficultyAdjustment, Epoch, Halving};
+pub use electrum_target::{ElectrumServerInfo, ElectrumTarget, ElectrumTransport};
+pub use error::{
+    CheckTargetError, MempoolError, ParseParameterError, ParseTargetError, RejectReason,
+    ResolveTargetError,
+};
+pub use failover::Backend;
+pub use fees::{feerate_for_target, MempoolBlock, RecommendedFees};
+pub use lightning::{
+    Channel, ChannelPolicy, ChannelStatus, ChannelSummary, LatestNetworkStats, LightningNode,
+    LightningSearch, NetworkStats, NodeRanking, NodeSummary, TopNode,
+};
+pub use mempool::{MempoolInfo, RecentTransaction};
+pub use mining::{
+    DifficultyEntry, Hashrate, HashrateEntry, Interval, MiningPools, Pool, PoolDetails,
+    PoolHashrate, PoolShare, PoolStats, RewardStats,
+};
+pub use network::Network;
+pub use p2p_target::{BitcoinP2pTarget, PeerInfo};
+pub use prices::{Currency, HistoricalPrice, Prices};
+pub use rate_limit::{RateLimiter, RetryPolicy};
 pub use resolve_policy::ResolvePolicy;
-pub use target::{Fqhn, IcmpTarget, Port, Status, Target, TcpTarget};
+pub use rpc_target::{BitcoinRpcTarget, BlockchainInfo, RpcAuth};
+pub use target::{Fqhn, HttpTarget, IcmpTarget, MempoolApiTarget, Port, Status, Target, TcpTarget};
+pub use transaction::{MerkleProof, Outspend, Transaction, TxStatus, Vin, Vout};
+pub use transport::{HttpMethod, HttpRequest, HttpResponse, HttpTransport};
+
+#[cfg(all(feature = "async", feature = "transport-reqwest"))]
+pub use transport::AsyncReqwestTransport;
+#[cfg(feature = "transport-reqwest")]
+pub use transport::ReqwestTransport;
+#[cfg(feature = "transport-ureq")]
+pub use transport::UreqTransport;
+#[cfg(feature = "async")]
+pub use transport::{AsyncHttpTransport, BlockingTransport};
 
+#[cfg(feature = "async")]
+pub use async_client::AsyncMempoolClient;
 #[cfg(feature = "async")]
 pub use async_target::{AsyncTarget, AsyncTargetExecutor, BoxedHandler, BoxedTarget, OldStatus};
+#[cfg(feature = "websocket")]
+pub use stream::{MempoolEvent, MempoolStream, Subscription};
@@ -0,0 +1,336 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Module containing types returned by the mempool.space lightning endpoints.
+
+// Imports
+use super::mining::u64_from_str_or_number;
+use serde::Deserialize;
+use std::fmt::{self};
+
+/// Lightning network statistics as returned by `GET /v1/lightning/statistics/latest`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LatestNetworkStats {
+    /// Statistics of the latest snapshot
+    pub latest: NetworkStats,
+    /// Statistics of the snapshot before, if any
+    pub previous: Option<NetworkStats>,
+}
+
+/// Statistics of a lightning network snapshot.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct NetworkStats {
+    /// Date of the snapshot, e.g. "2022-07-18T00:00:00.000Z"
+    pub added: String,
+    /// Number of public channels
+    pub channel_count: u64,
+    /// Number of public nodes
+    pub node_count: u64,
+    /// Sum of all channel capacities in satoshis
+    pub total_capacity: u64,
+    /// Number of nodes reachable via tor only
+    pub tor_nodes: u64,
+    /// Number of nodes reachable via clearnet only
+    pub clearnet_nodes: u64,
+    /// Number of nodes without announced address
+    pub unannounced_nodes: u64,
+    /// Number of nodes reachable via clearnet and tor
+    #[serde(default)]
+    pub clearnet_tor_nodes: u64,
+    /// Average channel capacity in satoshis
+    pub avg_capacity: f64,
+    /// Average fee rate in parts per million
+    pub avg_fee_rate: f64,
+    /// Average base fee in millisatoshis
+    pub avg_base_fee_mtokens: f64,
+    /// Median channel capacity in satoshis
+    pub med_capacity: f64,
+    /// Median fee rate in parts per million
+    pub med_fee_rate: f64,
+    /// Median base fee in millisatoshis
+    pub med_base_fee_mtokens: f64,
+}
+
+/// Nodes and channels matching a query as returned by `GET /v1/lightning/search`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LightningSearch {
+    /// Nodes whose alias or public key match the query
+    pub nodes: Vec<NodeSummary>,
+    /// Channels whose short channel id matches the query
+    pub channels: Vec<ChannelSummary>,
+}
+
+/// Summary of a node listed by [LightningSearch].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct NodeSummary {
+    /// Public key of the node
+    pub public_key: String,
+    /// Alias of the node
+    pub alias: String,
+    /// Sum of all channel capacities in satoshis
+    #[serde(default)]
+    pub capacity: Option<u64>,
+    /// Number of active channels
+    #[serde(default)]
+    pub channels: Option<u64>,
+}
+
+/// Summary of a channel listed by [LightningSearch].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ChannelSummary {
+    /// Channel id as 64 bit integer
+    pub id: String,
+    /// Short channel id, e.g. "761123x1234x1"
+    pub short_id: String,
+}
+
+/// A lightning node as returned by `GET /v1/lightning/nodes/:public_key`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LightningNode {
+    /// Public key of the node
+    pub public_key: String,
+    /// Alias of the node
+    pub alias: String,
+    /// Color of the node, e.g. "#ff9900"
+    pub color: String,
+    /// Time the node was first seen in seconds since the unix epoch
+    pub first_seen: u64,
+    /// Time of the last node announcement in seconds since the unix epoch
+    pub updated_at: u64,
+    /// Comma separated addresses the node is reachable on
+    #[serde(default)]
+    pub sockets: String,
+    /// Number of active channels
+    pub active_channel_count: u64,
+    /// Number of channels opened over all time
+    #[serde(default)]
+    pub opened_channel_count: u64,
+    /// Number of channels closed over all time
+    #[serde(default)]
+    pub closed_channel_count: u64,
+    /// Sum of all active channel capacities in satoshis
+    #[serde(deserialize_with = "u64_from_str_or_number")]
+    pub capacity: u64,
+    /// ISO code of the country the node is located in, if known
+    #[serde(default)]
+    pub iso_code: Option<String>,
+}
+
+/// A lightning channel as returned by `GET /v1/lightning/channels/:short_id`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Channel {
+    /// Channel id as 64 bit integer
+    pub id: String,
+    /// Short channel id, e.g. "761123x1234x1"
+    pub short_id: String,
+    /// Capacity of the channel in satoshis
+    pub capacity: u64,
+    /// Txid of the funding transaction
+    pub transaction_id: String,
+    /// Output index of the funding transaction
+    pub transaction_vout: u32,
+    /// Txid of the closing transaction, if closed
+    pub closing_transaction_id: Option<String>,
+    /// Status of the channel
+    pub status: ChannelStatus,
+    /// Policy of the first node
+    pub node_left: ChannelPolicy,
+    /// Policy of the second node
+    pub node_right: ChannelPolicy,
+}
+
+/// Status of a [Channel].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(from = "u8")]
+pub enum ChannelStatus {
+    /// The channel is announced but disabled or not yet confirmed
+    Inactive,
+    /// The channel is usable for routing
+    Active,
+    /// The funding output is spent
+    Closed,
+}
+
+impl From<u8> for ChannelStatus {
+    fn from(status: u8) -> Self {
+        match status {
+            1 => ChannelStatus::Active,
+            2 => ChannelStatus::Closed,
+            _ => ChannelStatus::Inactive,
+        }
+    }
+}
+
+/// Routing policy of one node of a [Channel].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ChannelPolicy {
+    /// Public key of the node
+    pub public_key: String,
+    /// Alias of the node
+    pub alias: String,
+    /// Base fee in millisatoshis
+    #[serde(deserialize_with = "u64_from_str_or_number")]
+    pub base_fee_mtokens: u64,
+    /// Fee rate in parts per million
+    pub fee_rate: u64,
+    /// CLTV delta required for forwarding
+    pub cltv_delta: u32,
+    /// True if the node disabled forwarding over the channel
+    pub is_disabled: bool,
+}
+
+/// Ranking of the top nodes as returned by `GET /v1/lightning/nodes/rankings/:ranking`.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub enum NodeRanking {
+    /// Nodes with the highest total channel capacity
+    Capacity,
+    /// Nodes with the most channels
+    Connectivity,
+}
+
+impl NodeRanking {
+    /// Get the path segment of this [NodeRanking] as used by the API, e.g. "liquidity".
+    pub fn code(&self) -> &'static str {
+        match self {
+            NodeRanking::Capacity => "liquidity",
+            NodeRanking::Connectivity => "connectivity",
+        }
+    }
+}
+
+impl fmt::Display for NodeRanking {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.code())
+    }
+}
+
+/// A node listed by `GET /v1/lightning/nodes/rankings/:ranking`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopNode {
+    /// Public key of the node
+    pub public_key: String,
+    /// Alias of the node
+    pub alias: String,
+    /// Number of active channels
+    pub channels: u64,
+    /// Sum of all active channel capacities in satoshis
+    #[serde(deserialize_with = "u64_from_str_or_number")]
+    pub capacity: u64,
+    /// Time the node was first seen in seconds since the unix epoch
+    pub first_seen: u64,
+    /// Time of the last node announcement in seconds since the unix epoch
+    pub updated_at: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn network_stats_deserialize() {
+        // Expectency: The latest and previous snapshot must be parsed into NetworkStats.
+        let stats = r#"{
+            "id": 163,
+            "added": "2022-08-30T00:00:00.000Z",
+            "channel_count": 81937,
+            "node_count": 15497,
+            "total_capacity": 476536413630,
+            "tor_nodes": 10108,
+            "clearnet_nodes": 2781,
+            "unannounced_nodes": 1011,
+            "avg_capacity": 5815860,
+            "avg_fee_rate": 514,
+            "avg_base_fee_mtokens": 889,
+            "med_capacity": 1800000,
+            "med_fee_rate": 81,
+            "med_base_fee_mtokens": 1000,
+            "clearnet_tor_nodes": 1597
+        }"#;
+        let json = format!(r#"{{"latest": {}, "previous": null}}"#, stats);
+        let stats: LatestNetworkStats = serde_json::from_str(&json).unwrap();
+        assert_eq!(stats.latest.node_count, 15497);
+        assert_eq!(stats.latest.med_fee_rate, 81.0);
+        assert_eq!(stats.previous, None);
+    }
+
+    #[test]
+    fn lightning_node_deserialize() {
+        // Expectency: A node must be parsed into a LightningNode, the capacity may be a string.
+        let json = r##"{
+            "public_key": "033ac2f9f7ff643c235cc247c521663924aff73b26b38118a6c6821460afcde1b3",
+            "alias": "Red.de.Rayos",
+            "first_seen": 1637708194,
+            "updated_at": 1662726279,
+            "color": "#68f442",
+            "sockets": "84.44.203.181:9735",
+            "as_number": 8422,
+            "iso_code": "DE",
+            "active_channel_count": 55,
+            "capacity": "31505027",
+            "opened_channel_count": 55,
+            "closed_channel_count": 111
+        }"##;
+        let node: LightningNode = serde_json::from_str(json).unwrap();
+        assert_eq!(node.alias, "Red.de.Rayos");
+        assert_eq!(node.capacity, 31505027);
+        assert_eq!(node.iso_code.as_deref(), Some("DE"));
+    }
+
+    #[test]
+    fn channel_deserialize() {
+        // Expectency: A channel must be parsed into a Channel with both policies.
+        let policy = |alias: &str, fee_rate: u64| {
+            format!(
+                r#"{{"alias": "{}", "public_key": "03ab", "channels": 3, "capacity": 1000,
+                    "base_fee_mtokens": "1000", "cltv_delta": 40, "fee_rate": {},
+                    "is_disabled": false, "max_htlc_mtokens": 990000000, "min_htlc_mtokens": 1000,
+                    "updated_at": "2022-08-25T23:05:40.000Z"}}"#,
+                alias, fee_rate
+            )
+        };
+        let json = format!(
+            r#"{{
+                "id": "768457472831193088",
+                "short_id": "698925x2156x0",
+                "capacity": 16777215,
+                "transaction_id": "9f248ff4b5a7d8e1f1d1a6e0e7f0f4c3a5a2e8b6c7d9f0e1a2b3c4d5e6f7a8b9",
+                "transaction_vout": 0,
+                "closing_transaction_id": null,
+                "closing_reason": null,
+                "updated_at": "2022-08-25T23:05:40.000Z",
+                "created": "2022-08-13T19:06:04.000Z",
+                "status": 1,
+                "node_left": {},
+                "node_right": {}
+            }}"#,
+            policy("left", 1),
+            policy("right", 500)
+        );
+        let channel: Channel = serde_json::from_str(&json).unwrap();
+        assert_eq!(channel.short_id, "698925x2156x0");
+        assert_eq!(channel.status, ChannelStatus::Active);
+        assert_eq!(channel.node_left.base_fee_mtokens, 1000);
+        assert_eq!(channel.node_right.fee_rate, 500);
+    }
+
+    #[test]
+    fn top_node_deserialize() {
+        // Expectency: The camel case keys of the rankings must map onto TopNode.
+        let json = r#"[{
+            "publicKey": "03864ef025fde8fb587d989186ce6a4a186895ee44a926bfc370e2c366597a3f8f",
+            "alias": "ACINQ",
+            "channels": 2907,
+            "capacity": 36010516297,
+            "firstSeen": 1522941222,
+            "updatedAt": 1661274935,
+            "city": null,
+            "country": {"en": "United States"}
+        }]"#;
+        let nodes: Vec<TopNode> = serde_json::from_str(json).unwrap();
+        assert_eq!(nodes[0].alias, "ACINQ");
+        assert_eq!(nodes[0].capacity, 36010516297);
+        assert_eq!(NodeRanking::Capacity.to_string(), "liquidity");
+    }
+}
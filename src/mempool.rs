@@ -0,0 +1,120 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Module containing types returned by the mempool.space mempool endpoints.
+
+// Imports
+use serde::Deserialize;
+
+/// Mempool backlog statistics as returned by `GET /mempool`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MempoolInfo {
+    /// Number of transactions in the mempool
+    pub count: u64,
+    /// Total virtual size of all mempool transactions
+    pub vsize: u64,
+    /// Total fees of all mempool transactions in satoshis
+    pub total_fee: u64,
+    /// Pairs of (feerate in sat/vB, vsize) grouping mempool transactions by feerate
+    pub fee_histogram: Vec<(f64, u64)>,
+}
+
+impl MempoolInfo {
+    /// Calculate the virtual size of all mempool transactions paying at least given feerate.
+    ///
+    /// # Arguments
+    /// * feerate: the feerate in sat/vB to compare against, e.g. of a transaction to replace.
+    ///
+    /// # Returns
+    /// The summed vsize of all fee histogram buckets whose lower feerate bound is at least
+    /// the given feerate.
+    ///
+    /// # Notes
+    /// Each bucket of the fee histogram covers the feerates from its own feerate up to the
+    /// feerate of the previous bucket. A bucket starting below the given feerate is not
+    /// counted, even if some of its transactions pay more, so the result is a lower bound.
+    ///
+    /// # Example
+    /// ```
+    /// # use mempool_space::MempoolInfo;
+    ///
+    /// let info = MempoolInfo {
+    ///     count: 3,
+    ///     vsize: 600,
+    ///     total_fee: 4500,
+    ///     fee_histogram: vec![(12.5, 200), (8.0, 100), (3.0, 300)],
+    /// };
+    /// assert_eq!(info.vbytes_ahead_of(10.0), 200);
+    /// assert_eq!(info.vbytes_ahead_of(8.0), 300);
+    /// ```
+    pub fn vbytes_ahead_of(&self, feerate: f64) -> u64 {
+        self.fee_histogram
+            .iter()
+            .filter(|(bucket_feerate, _)| *bucket_feerate >= feerate)
+            .map(|(_, vsize)| vsize)
+            .sum()
+    }
+}
+
+/// A recently added mempool transaction as returned by `GET /mempool/recent`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RecentTransaction {
+    /// Transaction id
+    pub txid: String,
+    /// Fee paid in satoshis
+    pub fee: u64,
+    /// Virtual size of the transaction
+    pub vsize: u64,
+    /// Sum of all output values in satoshis
+    pub value: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mempool_info_deserialize() {
+        // Expectency: The fee histogram must be parsed into (feerate, vsize) tuples.
+        let json = r#"{
+            "count": 3,
+            "vsize": 600,
+            "total_fee": 4500,
+            "fee_histogram": [[12.5, 200], [3.0, 400]]
+        }"#;
+        let info: MempoolInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(info.count, 3);
+        assert_eq!(info.fee_histogram, vec![(12.5, 200), (3.0, 400)]);
+    }
+
+    #[test]
+    fn mempool_info_vbytes_ahead_of() {
+        // Expectency: Buckets at or above the feerate are summed, buckets below are ignored.
+        let info = MempoolInfo {
+            count: 4,
+            vsize: 1000,
+            total_fee: 10000,
+            fee_histogram: vec![(50.0, 100), (12.5, 200), (3.0, 400), (1.0, 300)],
+        };
+        assert_eq!(info.vbytes_ahead_of(100.0), 0);
+        assert_eq!(info.vbytes_ahead_of(50.0), 100);
+        assert_eq!(info.vbytes_ahead_of(12.0), 300);
+        assert_eq!(info.vbytes_ahead_of(1.0), 1000);
+        assert_eq!(info.vbytes_ahead_of(0.0), info.vsize);
+    }
+
+    #[test]
+    fn recent_transaction_deserialize() {
+        // Expectency: Recent transactions must be parsed into RecentTransaction.
+        let json = r#"[{
+            "txid": "4b93c138293a7e3dfea6f0a63d944890b5ba571b03cc22d8c66995535e90dce8",
+            "fee": 18277,
+            "vsize": 2585,
+            "value": 4972029
+        }]"#;
+        let recent: Vec<RecentTransaction> = serde_json::from_str(json).unwrap();
+        assert_eq!(recent[0].fee, 18277);
+        assert_eq!(recent[0].vsize, 2585);
+    }
+}
@@ -0,0 +1,394 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Module containing types returned by the mempool.space mining endpoints.
+
+// Imports
+use super::ParseParameterError;
+use serde::{Deserialize, Deserializer};
+use std::fmt::{self};
+use std::str::FromStr;
+
+/// Time intervals accepted by the mining endpoints.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub enum Interval {
+    /// Last 24 hours, "24h"
+    Day,
+    /// Last 3 days, "3d"
+    ThreeDays,
+    /// Last week, "1w"
+    Week,
+    /// Last month, "1m"
+    Month,
+    /// Last 3 months, "3m"
+    ThreeMonths,
+    /// Last 6 months, "6m"
+    SixMonths,
+    /// Last year, "1y"
+    Year,
+    /// Last 2 years, "2y"
+    TwoYears,
+    /// Last 3 years, "3y"
+    ThreeYears,
+}
+
+impl Interval {
+    /// All accepted intervals
+    pub const ALL: [Interval; 9] = [
+        Interval::Day,
+        Interval::ThreeDays,
+        Interval::Week,
+        Interval::Month,
+        Interval::ThreeMonths,
+        Interval::SixMonths,
+        Interval::Year,
+        Interval::TwoYears,
+        Interval::ThreeYears,
+    ];
+
+    /// Get the representation of this [Interval] as used by the API, e.g. "24h".
+    pub fn code(&self) -> &'static str {
+        match self {
+            Interval::Day => "24h",
+            Interval::ThreeDays => "3d",
+            Interval::Week => "1w",
+            Interval::Month => "1m",
+            Interval::ThreeMonths => "3m",
+            Interval::SixMonths => "6m",
+            Interval::Year => "1y",
+            Interval::TwoYears => "2y",
+            Interval::ThreeYears => "3y",
+        }
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.code())
+    }
+}
+
+impl FromStr for Interval {
+    type Err = ParseParameterError;
+
+    fn from_str(s: &str) -> Result<Interval, Self::Err> {
+        Interval::ALL
+            .into_iter()
+            .find(|interval| interval.code().eq_ignore_ascii_case(s))
+            .ok_or(ParseParameterError::from("Unknown interval"))
+    }
+}
+
+/// Block counts of all mining pools as returned by `GET /v1/mining/pools/:interval`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MiningPools {
+    /// Statistics per mining pool, ordered by rank
+    pub pools: Vec<PoolStats>,
+    /// Number of blocks mined within the interval
+    pub block_count: u64,
+    /// Estimated network hashrate in hashes per second
+    pub last_estimated_hashrate: f64,
+}
+
+/// Statistics of a single mining pool within [MiningPools].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolStats {
+    /// Id of the mining pool
+    pub pool_id: u64,
+    /// Name of the mining pool
+    pub name: String,
+    /// Website of the mining pool
+    pub link: String,
+    /// Number of blocks mined within the interval
+    pub block_count: u64,
+    /// Rank by number of blocks mined, starting at 1
+    pub rank: u64,
+    /// Number of blocks without transactions besides the coinbase
+    pub empty_blocks: u64,
+    /// Slug identifying the mining pool in other endpoints, e.g. "foundryusa"
+    pub slug: String,
+}
+
+/// Details of a mining pool as returned by `GET /v1/mining/pool/:slug`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolDetails {
+    /// Identification of the mining pool
+    pub pool: Pool,
+    /// Number of blocks mined
+    pub block_count: PoolShare<u64>,
+    /// Share of all blocks mined, between 0 and 1
+    pub block_share: PoolShare<f64>,
+    /// Estimated hashrate in hashes per second
+    pub estimated_hashrate: f64,
+    /// Hashrate reported by the mining pool in hashes per second, if any
+    pub reported_hashrate: Option<f64>,
+}
+
+/// Identification of a mining pool.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Pool {
+    /// Id of the mining pool
+    pub id: u64,
+    /// Name of the mining pool
+    pub name: String,
+    /// Website of the mining pool
+    pub link: String,
+    /// Known coinbase payout addresses
+    #[serde(default)]
+    pub addresses: Vec<String>,
+    /// Patterns matched against coinbase tags
+    #[serde(default)]
+    pub regexes: Vec<String>,
+    /// Slug identifying the mining pool in other endpoints
+    pub slug: String,
+}
+
+/// Value of a mining pool over all time, the last 24 hours and the last week.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PoolShare<T> {
+    /// Value over all time
+    pub all: T,
+    /// Value over the last 24 hours
+    #[serde(rename = "24h")]
+    pub day: T,
+    /// Value over the last week
+    #[serde(rename = "1w")]
+    pub week: T,
+}
+
+/// Hashrate of a mining pool as returned by `GET /v1/mining/pool/:slug/hashrate`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolHashrate {
+    /// Time of the data point in seconds since the unix epoch
+    pub timestamp: u64,
+    /// Average hashrate in hashes per second
+    pub avg_hashrate: f64,
+    /// Share of the network hashrate, between 0 and 1
+    pub share: f64,
+    /// Name of the mining pool
+    pub pool_name: String,
+}
+
+/// Network hashrate and difficulty as returned by `GET /v1/mining/hashrate/:interval`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hashrate {
+    /// Average network hashrate per day
+    pub hashrates: Vec<HashrateEntry>,
+    /// Difficulty adjustments within the interval
+    pub difficulty: Vec<DifficultyEntry>,
+    /// Current network hashrate in hashes per second
+    pub current_hashrate: f64,
+    /// Current difficulty
+    pub current_difficulty: f64,
+}
+
+/// Average network hashrate at a point in time.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HashrateEntry {
+    /// Time of the data point in seconds since the unix epoch
+    pub timestamp: u64,
+    /// Average hashrate in hashes per second
+    pub avg_hashrate: f64,
+}
+
+/// Difficulty adjustment as listed by [Hashrate] and `GET /v1/mining/difficulty-adjustments`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(from = "RawDifficultyEntry")]
+pub struct DifficultyEntry {
+    /// Time of the adjustment in seconds since the unix epoch
+    pub time: u64,
+    /// Height of the adjustment
+    pub height: u64,
+    /// Difficulty after the adjustment
+    pub difficulty: f64,
+    /// Ratio of the new to the previous difficulty
+    pub adjustment: f64,
+}
+
+/// Wire format of [DifficultyEntry]. The difficulty-adjustments endpoint returns tuples
+/// of [time, height, difficulty, adjustment] instead of objects.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawDifficultyEntry {
+    Object {
+        time: u64,
+        height: u64,
+        difficulty: f64,
+        adjustment: f64,
+    },
+    Tuple(u64, u64, f64, f64),
+}
+
+impl From<RawDifficultyEntry> for DifficultyEntry {
+    fn from(raw: RawDifficultyEntry) -> Self {
+        let (time, height, difficulty, adjustment) = match raw {
+            RawDifficultyEntry::Object {
+                time,
+                height,
+                difficulty,
+                adjustment,
+            } => (time, height, difficulty, adjustment),
+            RawDifficultyEntry::Tuple(time, height, difficulty, adjustment) => {
+                (time, height, difficulty, adjustment)
+            }
+        };
+        DifficultyEntry {
+            time,
+            height,
+            difficulty,
+            adjustment,
+        }
+    }
+}
+
+/// Reward statistics of recent blocks as returned by `GET /v1/mining/reward-stats/:blockCount`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RewardStats {
+    /// Height of the first block considered
+    pub start_block: u64,
+    /// Height of the last block considered
+    pub end_block: u64,
+    /// Sum of subsidies and fees in satoshis
+    #[serde(deserialize_with = "u64_from_str_or_number")]
+    pub total_reward: u64,
+    /// Sum of fees in satoshis
+    #[serde(deserialize_with = "u64_from_str_or_number")]
+    pub total_fee: u64,
+    /// Number of transactions
+    #[serde(deserialize_with = "u64_from_str_or_number")]
+    pub total_tx: u64,
+}
+
+/// Deserialize an u64 sent either as number or as decimal string.
+pub(crate) fn u64_from_str_or_number<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<u64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StrOrNumber {
+        Str(String),
+        Number(u64),
+    }
+
+    match StrOrNumber::deserialize(deserializer)? {
+        StrOrNumber::Str(s) => s.parse().map_err(serde::de::Error::custom),
+        StrOrNumber::Number(n) => Ok(n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interval_from_str() {
+        // Expectency: Intervals are parsed case insensitive and roundtrip via Display.
+        assert_eq!(Interval::from_str("24h").unwrap(), Interval::Day);
+        assert_eq!(Interval::from_str("3Y").unwrap(), Interval::ThreeYears);
+        for interval in Interval::ALL {
+            assert_eq!(Interval::from_str(&interval.to_string()).unwrap(), interval);
+        }
+        assert_eq!(
+            format!("{}", Interval::from_str("2w").unwrap_err()),
+            "Unknown interval"
+        );
+    }
+
+    #[test]
+    fn mining_pools_deserialize() {
+        // Expectency: The camel case keys of the API must map onto MiningPools.
+        let json = r#"{
+            "pools": [{
+                "poolId": 111,
+                "name": "Foundry USA",
+                "link": "https://foundrydigital.com/",
+                "blockCount": 194,
+                "rank": 1,
+                "emptyBlocks": 0,
+                "slug": "foundryusa",
+                "avgMatchRate": 99.96
+            }],
+            "blockCount": 1005,
+            "lastEstimatedHashrate": 6.3e20
+        }"#;
+        let pools: MiningPools = serde_json::from_str(json).unwrap();
+        assert_eq!(pools.block_count, 1005);
+        assert_eq!(pools.pools[0].slug, "foundryusa");
+        assert_eq!(pools.pools[0].rank, 1);
+    }
+
+    #[test]
+    fn pool_details_deserialize() {
+        // Expectency: Shares per period must map onto PoolShare.
+        let json = r#"{
+            "pool": {
+                "id": 111,
+                "name": "Foundry USA",
+                "link": "https://foundrydigital.com/",
+                "addresses": ["12KKDt4Mj7N5UAkQMN7LtPZMayenXHa8KL"],
+                "regexes": ["/2cDw/"],
+                "slug": "foundryusa",
+                "unique_id": 44
+            },
+            "blockCount": {"all": 67466, "24h": 38, "1w": 248},
+            "blockShare": {"all": 0.086, "24h": 0.263, "1w": 0.245},
+            "estimatedHashrate": 1.64e20,
+            "reportedHashrate": null
+        }"#;
+        let details: PoolDetails = serde_json::from_str(json).unwrap();
+        assert_eq!(details.pool.id, 111);
+        assert_eq!(details.block_count.day, 38);
+        assert_eq!(details.block_share.week, 0.245);
+        assert_eq!(details.reported_hashrate, None);
+    }
+
+    #[test]
+    fn difficulty_entry_deserialize() {
+        // Expectency: Difficulty adjustments must be parsed from objects and tuples alike.
+        let json = r#"{
+            "hashrates": [{"timestamp": 1652486400, "avgHashrate": 2.3e20}],
+            "difficulty": [{"time": 1652468330, "height": 736249, "difficulty": 3.1e13, "adjustment": 1.04}],
+            "currentHashrate": 2.5e20,
+            "currentDifficulty": 3.1e13
+        }"#;
+        let hashrate: Hashrate = serde_json::from_str(json).unwrap();
+        assert_eq!(hashrate.hashrates[0].timestamp, 1652486400);
+        assert_eq!(hashrate.difficulty[0].height, 736249);
+
+        let json = r#"[[1703311464, 822528, 72006146478567.1, 1.06983]]"#;
+        let adjustments: Vec<DifficultyEntry> = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            adjustments[0],
+            DifficultyEntry {
+                time: 1703311464,
+                height: 822528,
+                difficulty: 72006146478567.1,
+                adjustment: 1.06983,
+            }
+        );
+    }
+
+    #[test]
+    fn reward_stats_deserialize() {
+        // Expectency: Totals sent as decimal strings must be parsed into numbers.
+        let json = r#"{
+            "startBlock": 736549,
+            "endBlock": 736648,
+            "totalReward": "63223509383",
+            "totalFee": 1723509383,
+            "totalTx": "192718"
+        }"#;
+        let stats: RewardStats = serde_json::from_str(json).unwrap();
+        assert_eq!(stats.total_reward, 63223509383);
+        assert_eq!(stats.total_fee, 1723509383);
+        assert_eq!(stats.total_tx, 192718);
+    }
+}
@@ -0,0 +1,233 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Module containing the networks served by mempool.space and their API base urls.
+
+// Imports
+use super::target::Port;
+use super::ParseParameterError;
+use std::fmt::{self};
+use std::str::FromStr;
+
+// Documentation imports
+#[cfg(doc)]
+use super::MempoolClient;
+
+/// Network a [MempoolClient] sends its requests to.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub enum Network {
+    /// Bitcoin mainnet served by mempool.space
+    #[default]
+    Mainnet,
+    /// Bitcoin testnet3 served by mempool.space
+    Testnet,
+    /// Bitcoin testnet4 served by mempool.space
+    Testnet4,
+    /// Bitcoin signet served by mempool.space
+    Signet,
+    /// Liquid sidechain served by liquid.network
+    Liquid,
+    /// Self-hosted mempool instance reachable under the given API base url,
+    /// e.g. "http://192.168.1.10:8999/api"
+    Custom(String),
+}
+
+impl Network {
+    /// Get the API base url of this [Network].
+    ///
+    /// # Example
+    /// ```
+    /// # use mempool_space::Network;
+    ///
+    /// assert_eq!(Network::Signet.base_url(), "https://mempool.space/signet/api");
+    /// ```
+    pub fn base_url(&self) -> &str {
+        match self {
+            Network::Mainnet => "https://mempool.space/api",
+            Network::Testnet => "https://mempool.space/testnet/api",
+            Network::Testnet4 => "https://mempool.space/testnet4/api",
+            Network::Signet => "https://mempool.space/signet/api",
+            Network::Liquid => "https://liquid.network/api",
+            Network::Custom(base_url) => base_url.trim_end_matches('/'),
+        }
+    }
+
+    /// Get the url of the WebSocket API of this [Network].
+    ///
+    /// # Example
+    /// ```
+    /// # use mempool_space::Network;
+    ///
+    /// assert_eq!(Network::Mainnet.websocket_url(), "wss://mempool.space/api/v1/ws");
+    /// ```
+    pub fn websocket_url(&self) -> String {
+        let base_url = self.base_url();
+        let base_url = match base_url.split_once("://") {
+            Some(("https", rest)) => format!("wss://{}", rest),
+            Some(("http", rest)) => format!("ws://{}", rest),
+            _ => String::from(base_url),
+        };
+        format!("{}/v1/ws", base_url)
+    }
+
+    /// Get the magic bytes starting each P2P message of this [Network].
+    ///
+    /// # Returns
+    /// The magic bytes of the Bitcoin networks, None for Liquid and self-hosted instances.
+    ///
+    /// # Example
+    /// ```
+    /// # use mempool_space::Network;
+    ///
+    /// assert_eq!(Network::Mainnet.p2p_magic(), Some([0xf9, 0xbe, 0xb4, 0xd9]));
+    /// ```
+    pub fn p2p_magic(&self) -> Option<[u8; 4]> {
+        match self {
+            Network::Mainnet => Some([0xf9, 0xbe, 0xb4, 0xd9]),
+            Network::Testnet => Some([0x0b, 0x11, 0x09, 0x07]),
+            Network::Testnet4 => Some([0x1c, 0x16, 0x3f, 0x28]),
+            Network::Signet => Some([0x0a, 0x03, 0xcf, 0x40]),
+            Network::Liquid | Network::Custom(_) => None,
+        }
+    }
+
+    /// Get the default P2P port of this [Network].
+    ///
+    /// # Returns
+    /// The default port of the Bitcoin networks, None for Liquid and self-hosted instances.
+    pub fn p2p_port(&self) -> Option<Port> {
+        match self {
+            Network::Mainnet => Some(8333),
+            Network::Testnet => Some(18333),
+            Network::Testnet4 => Some(48333),
+            Network::Signet => Some(38333),
+            Network::Liquid | Network::Custom(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Network::Mainnet => write!(formatter, "mainnet"),
+            Network::Testnet => write!(formatter, "testnet"),
+            Network::Testnet4 => write!(formatter, "testnet4"),
+            Network::Signet => write!(formatter, "signet"),
+            Network::Liquid => write!(formatter, "liquid"),
+            Network::Custom(base_url) => write!(formatter, "{}", base_url),
+        }
+    }
+}
+
+impl FromStr for Network {
+    type Err = ParseParameterError;
+
+    /// Parse a network name like "signet" or the base url of a self-hosted instance
+    /// starting with "http://" or "https://".
+    fn from_str(s: &str) -> Result<Network, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mainnet" | "bitcoin" => Ok(Network::Mainnet),
+            "testnet" | "testnet3" => Ok(Network::Testnet),
+            "testnet4" => Ok(Network::Testnet4),
+            "signet" => Ok(Network::Signet),
+            "liquid" => Ok(Network::Liquid),
+            lower if lower.starts_with("http://") || lower.starts_with("https://") => {
+                Ok(Network::Custom(String::from(s)))
+            }
+            _ => Err(ParseParameterError::from("Unknown network")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn network_base_url() {
+        // Expectency: Each network must map onto its mempool.space API base url.
+        assert_eq!(Network::Mainnet.base_url(), "https://mempool.space/api");
+        assert_eq!(
+            Network::Testnet.base_url(),
+            "https://mempool.space/testnet/api"
+        );
+        assert_eq!(
+            Network::Testnet4.base_url(),
+            "https://mempool.space/testnet4/api"
+        );
+        assert_eq!(
+            Network::Signet.base_url(),
+            "https://mempool.space/signet/api"
+        );
+        assert_eq!(Network::Liquid.base_url(), "https://liquid.network/api");
+        assert_eq!(
+            Network::Custom(String::from("http://192.168.1.10:8999/api/")).base_url(),
+            "http://192.168.1.10:8999/api"
+        );
+    }
+
+    #[test]
+    fn network_websocket_url() {
+        // Expectency: The WebSocket url is derived from the base url using the ws(s) scheme.
+        assert_eq!(
+            Network::Testnet4.websocket_url(),
+            "wss://mempool.space/testnet4/api/v1/ws"
+        );
+        assert_eq!(
+            Network::Custom(String::from("http://192.168.1.10:8999/api")).websocket_url(),
+            "ws://192.168.1.10:8999/api/v1/ws"
+        );
+    }
+
+    #[test]
+    fn network_from_str_valid() {
+        // Expectency: Network names are parsed case insensitive, urls are parsed into
+        //             custom networks.
+        assert_eq!(Network::from_str("mainnet").unwrap(), Network::Mainnet);
+        assert_eq!(Network::from_str("Testnet").unwrap(), Network::Testnet);
+        assert_eq!(Network::from_str("testnet4").unwrap(), Network::Testnet4);
+        assert_eq!(Network::from_str("SIGNET").unwrap(), Network::Signet);
+        assert_eq!(Network::from_str("liquid").unwrap(), Network::Liquid);
+        assert_eq!(
+            Network::from_str("http://192.168.1.10:8999/api").unwrap(),
+            Network::Custom(String::from("http://192.168.1.10:8999/api"))
+        );
+    }
+
+    #[test]
+    fn network_from_str_invalid() {
+        // Expectency: Unknown network names must lead to an error.
+        assert_eq!(
+            format!("{}", Network::from_str("regtest").unwrap_err()),
+            "Unknown network"
+        );
+    }
+
+    #[test]
+    fn network_display_roundtrip() {
+        // Expectency: The display representation of a network must parse into the same network.
+        for network in [
+            Network::Mainnet,
+            Network::Testnet,
+            Network::Testnet4,
+            Network::Signet,
+            Network::Liquid,
+            Network::Custom(String::from("http://localhost:8999/api")),
+        ] {
+            assert_eq!(Network::from_str(&network.to_string()).unwrap(), network);
+        }
+    }
+
+    #[test]
+    fn network_p2p_magic_and_port() {
+        // Expectency: Bitcoin networks map onto their magic bytes and default ports.
+        assert_eq!(
+            Network::Testnet4.p2p_magic(),
+            Some([0x1c, 0x16, 0x3f, 0x28])
+        );
+        assert_eq!(Network::Signet.p2p_port(), Some(38333));
+        assert_eq!(Network::Liquid.p2p_magic(), None);
+        assert_eq!(Network::Custom(String::from("http://a")).p2p_port(), None);
+    }
+}
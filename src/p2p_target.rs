@@ -0,0 +1,469 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Module containing a "Target" speaking the Bitcoin P2P wire protocol.
+
+// Imports
+use super::target::{
+    invalid_data, probe_addrs, Fqhn, Port, Status, Target, DEFAULT_TCP_CONNECT_TIMEOUT,
+};
+use super::{CheckTargetError, Network, ParseTargetError, ResolvePolicy, TcpTarget};
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+/// Protocol version announced in the version message of a [BitcoinP2pTarget]
+pub const P2P_PROTOCOL_VERSION: i32 = 70016;
+
+/// User agent announced in the version message of a [BitcoinP2pTarget]
+pub const P2P_USER_AGENT: &str = concat!("/mempool_space:", env!("CARGO_PKG_VERSION"), "/");
+
+/// Maximum number of messages read until the handshake must be completed
+const MAX_HANDSHAKE_MESSAGES: usize = 16;
+
+/// Maximum payload size of a message accepted during the handshake
+const MAX_PAYLOAD_SIZE: usize = 1024 * 1024;
+
+/// Information a peer announced in its version message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerInfo {
+    /// P2P protocol version of the peer, e.g. 70016
+    pub protocol_version: i32,
+    /// Service flags of the peer, e.g. 0x409 for NODE_NETWORK, NODE_WITNESS and NODE_NETWORK_LIMITED
+    pub services: u64,
+    /// User agent of the peer, e.g. "/Satoshi:27.0.0/"
+    pub user_agent: String,
+    /// Height of the best block of the peer
+    pub start_height: i32,
+}
+
+/// Target to check if a Bitcoin node answers the P2P handshake.
+///
+/// # Notes
+/// BitcoinP2pTargets connect to the node, send a version message and wait for the nodes
+/// version and verack messages. Peers answering with the magic bytes of another network are
+/// not available. The [PeerInfo] of the last successful handshake is kept.
+///
+/// Like [TcpTarget], the checks block up to connect_timeout per address and message.
+///
+/// # Example
+/// ```no_run
+/// # use std::str::FromStr;
+/// # use mempool_space::{BitcoinP2pTarget, Network, Status, Target};
+///
+/// let target = BitcoinP2pTarget::from_str("127.0.0.1:38333")
+///     .unwrap()
+///     .set_magic(Network::Signet.p2p_magic().unwrap());
+/// if target.check_availability().unwrap() == Status::Available {
+///     println!("{:?}", target.get_peer_info());
+/// }
+/// ```
+#[derive(Debug)]
+pub struct BitcoinP2pTarget {
+    /// [Fqhn] specifying the node to connect to.
+    fqhn: Fqhn,
+    /// [Port] specifying the P2P port to connect to.
+    port: Port,
+    /// Magic bytes of the network the node must belong to.
+    magic: [u8; 4],
+    /// [Duration] used as connect_timeout and as timeout of each read and write.
+    connect_timeout: Duration,
+    /// [ResolvePolicy] to apply during resolution of fqhn to IP addresses.
+    resolve_policy: ResolvePolicy,
+    /// [PeerInfo] of the last successful handshake.
+    peer_info: Mutex<Option<PeerInfo>>,
+}
+
+impl BitcoinP2pTarget {
+    /// Construct a [BitcoinP2pTarget].
+    ///
+    /// # Arguments
+    /// * fqhn: string containing "fully qualified domain name" e.g. "::1", "localhost".
+    /// * port: P2P port number to connect to.
+    /// * magic: magic bytes of the network, see [Network::p2p_magic].
+    /// * connect_timeout: [Duration] used as connection attempt and message timeout.
+    /// * resolve_policy: the [ResolvePolicy] to use for this [Target].
+    ///
+    /// # Returns
+    /// Instance of [BitcoinP2pTarget].
+    ///
+    /// # Notes
+    /// For more convenience use the implementations of trait "From" and "FromStr".
+    pub fn new(
+        fqhn: Fqhn,
+        port: Port,
+        magic: [u8; 4],
+        connect_timeout: Duration,
+        resolve_policy: ResolvePolicy,
+    ) -> Self {
+        BitcoinP2pTarget {
+            fqhn,
+            port,
+            magic,
+            connect_timeout,
+            resolve_policy,
+            peer_info: Mutex::new(None),
+        }
+    }
+
+    /// Set new magic bytes of the network the node must belong to.
+    pub fn set_magic(mut self, magic: [u8; 4]) -> Self {
+        self.magic = magic;
+        self
+    }
+
+    /// Set a new [ResolvePolicy] for name resolution.
+    pub fn set_resolve_policy(mut self, resolve_policy: ResolvePolicy) -> Self {
+        self.resolve_policy = resolve_policy;
+        self
+    }
+
+    /// Set a new connect_timeout [Duration] used for connection attempts and each message.
+    pub fn set_connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = connect_timeout;
+        self
+    }
+
+    /// Get a reference to the [Fqhn].
+    pub fn get_fqhn(&self) -> &Fqhn {
+        &self.fqhn
+    }
+
+    /// Get a reference to the P2P [Port] number in use.
+    pub fn get_portnumber(&self) -> &Port {
+        &self.port
+    }
+
+    /// Get a reference to the magic bytes in use.
+    pub fn get_magic(&self) -> &[u8; 4] {
+        &self.magic
+    }
+
+    /// Get a reference to the connect_timeout [Duration] in use.
+    pub fn get_connect_timeout(&self) -> &Duration {
+        &self.connect_timeout
+    }
+
+    /// Get a reference to the [ResolvePolicy] in use.
+    pub fn get_resolve_policy(&self) -> &ResolvePolicy {
+        &self.resolve_policy
+    }
+
+    /// Get the [PeerInfo] of the last successful handshake, if any.
+    pub fn get_peer_info(&self) -> Option<PeerInfo> {
+        self.peer_info.lock().unwrap().clone()
+    }
+
+    /// Perform the version/verack handshake with given address.
+    ///
+    /// # Returns
+    /// * On success, the [PeerInfo] of the peer.
+    /// * On failure, an [io::Error] if the connection failed, the peer misbehaved or belongs
+    ///   to another network.
+    fn handshake(&self, addr: SocketAddr) -> io::Result<PeerInfo> {
+        let mut stream = TcpStream::connect_timeout(&addr, self.connect_timeout)?;
+        stream.set_read_timeout(Some(self.connect_timeout))?;
+        stream.set_write_timeout(Some(self.connect_timeout))?;
+
+        let version = version_payload(addr);
+        stream.write_all(&encode_message(self.magic, "version", &version))?;
+
+        let mut peer_info = None;
+        let mut verack_received = false;
+        for _ in 0..MAX_HANDSHAKE_MESSAGES {
+            let (command, payload) = read_message(&mut stream, self.magic)?;
+            match command.as_str() {
+                "version" => {
+                    peer_info = Some(parse_version(&payload)?);
+                    stream.write_all(&encode_message(self.magic, "verack", &[]))?;
+                }
+                "verack" => verack_received = true,
+                // Note: Peers may send e.g. wtxidrelay or sendaddrv2 before verack
+                _ => (),
+            }
+            if let (Some(peer_info), true) = (&peer_info, verack_received) {
+                return Ok(peer_info.clone());
+            }
+        }
+        Err(invalid_data("Handshake not completed"))
+    }
+}
+
+impl Target for BitcoinP2pTarget {
+    fn get_id(&self) -> String {
+        format!("{}:{}", self.get_fqhn(), self.get_portnumber())
+    }
+
+    fn check_availability(&self) -> Result<Status, CheckTargetError> {
+        let peer_info = probe_addrs(&self.resolve_policy, &self.fqhn, self.port, |addr| {
+            self.handshake(addr)
+        })?;
+        match peer_info {
+            Some(peer_info) => {
+                *self.peer_info.lock().unwrap() = Some(peer_info);
+                Ok(Status::Available)
+            }
+            None => Ok(Status::NotAvailable),
+        }
+    }
+}
+
+impl From<SocketAddr> for BitcoinP2pTarget {
+    /// Build a [BitcoinP2pTarget] checking a mainnet node at given address.
+    fn from(socket: SocketAddr) -> Self {
+        let target = TcpTarget::from(socket);
+        BitcoinP2pTarget::new(
+            target.get_fqhn().clone(),
+            *target.get_portnumber(),
+            Network::Mainnet.p2p_magic().unwrap_or_default(),
+            DEFAULT_TCP_CONNECT_TIMEOUT,
+            *target.get_resolve_policy(),
+        )
+    }
+}
+
+impl FromStr for BitcoinP2pTarget {
+    type Err = ParseTargetError;
+
+    /// Parse a [BitcoinP2pTarget] checking a mainnet node from "host:port".
+    fn from_str(s: &str) -> Result<BitcoinP2pTarget, Self::Err> {
+        let target = TcpTarget::from_str(s)?;
+        Ok(BitcoinP2pTarget::new(
+            target.get_fqhn().clone(),
+            *target.get_portnumber(),
+            Network::Mainnet.p2p_magic().unwrap_or_default(),
+            DEFAULT_TCP_CONNECT_TIMEOUT,
+            *target.get_resolve_policy(),
+        ))
+    }
+}
+
+/// Calculate the checksum of a payload: the first 4 bytes of its double SHA-256.
+fn checksum(payload: &[u8]) -> [u8; 4] {
+    let hash = Sha256::digest(Sha256::digest(payload));
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Encode a P2P message consisting of header and payload.
+fn encode_message(magic: [u8; 4], command: &str, payload: &[u8]) -> Vec<u8> {
+    let mut command_bytes = [0u8; 12];
+    command_bytes[..command.len()].copy_from_slice(command.as_bytes());
+
+    let mut message = Vec::with_capacity(24 + payload.len());
+    message.extend_from_slice(&magic);
+    message.extend_from_slice(&command_bytes);
+    message.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    message.extend_from_slice(&checksum(payload));
+    message.extend_from_slice(payload);
+    message
+}
+
+/// Read a P2P message from given stream.
+///
+/// # Returns
+/// * On success, command and payload of the message.
+/// * On failure, an [io::Error] if reading failed, the magic bytes do not match,
+///   the payload is too large or the checksum is invalid.
+fn read_message(stream: &mut impl Read, magic: [u8; 4]) -> io::Result<(String, Vec<u8>)> {
+    let mut header = [0u8; 24];
+    stream.read_exact(&mut header)?;
+    if header[..4] != magic {
+        return Err(invalid_data("Peer belongs to another network"));
+    }
+
+    let command = String::from_utf8_lossy(&header[4..16])
+        .trim_end_matches('\0')
+        .to_string();
+    let length = u32::from_le_bytes([header[16], header[17], header[18], header[19]]) as usize;
+    if length > MAX_PAYLOAD_SIZE {
+        return Err(invalid_data("Payload too large"));
+    }
+
+    let mut payload = vec![0u8; length];
+    stream.read_exact(&mut payload)?;
+    if header[20..24] != checksum(&payload) {
+        return Err(invalid_data("Invalid checksum"));
+    }
+    Ok((command, payload))
+}
+
+/// Encode the payload of the version message sent to given address.
+fn version_payload(addr: SocketAddr) -> Vec<u8> {
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    let ip = match addr {
+        SocketAddr::V4(addr) => addr.ip().to_ipv6_mapped(),
+        SocketAddr::V6(addr) => *addr.ip(),
+    };
+    let nonce = RandomState::new().build_hasher().finish();
+
+    let mut payload = Vec::new();
+    payload.extend_from_slice(&P2P_PROTOCOL_VERSION.to_le_bytes());
+    payload.extend_from_slice(&0u64.to_le_bytes()); // services
+    payload.extend_from_slice(&timestamp.to_le_bytes());
+    payload.extend_from_slice(&0u64.to_le_bytes()); // addr_recv services
+    payload.extend_from_slice(&ip.octets());
+    payload.extend_from_slice(&addr.port().to_be_bytes());
+    payload.extend_from_slice(&[0u8; 26]); // addr_from
+    payload.extend_from_slice(&nonce.to_le_bytes());
+    payload.push(P2P_USER_AGENT.len() as u8);
+    payload.extend_from_slice(P2P_USER_AGENT.as_bytes());
+    payload.extend_from_slice(&0i32.to_le_bytes()); // start_height
+    payload.push(0); // relay
+    payload
+}
+
+/// Parse the payload of a version message into a [PeerInfo].
+fn parse_version(payload: &[u8]) -> io::Result<PeerInfo> {
+    let mut reader = payload;
+    let mut read = |len: usize| -> io::Result<Vec<u8>> {
+        let mut buf = vec![0u8; len];
+        reader.read_exact(&mut buf)?;
+        Ok(buf)
+    };
+
+    let protocol_version = i32::from_le_bytes(read(4)?.try_into().unwrap());
+    let services = u64::from_le_bytes(read(8)?.try_into().unwrap());
+    read(8 + 26 + 26 + 8)?; // timestamp, addr_recv, addr_from and nonce
+
+    let user_agent_len = match read(1)?[0] {
+        0xfd => u16::from_le_bytes(read(2)?.try_into().unwrap()) as usize,
+        0xfe | 0xff => return Err(invalid_data("User agent too long")),
+        len => len as usize,
+    };
+    let user_agent = String::from_utf8_lossy(&read(user_agent_len)?).into_owned();
+    let start_height = i32::from_le_bytes(read(4)?.try_into().unwrap());
+
+    Ok(PeerInfo {
+        protocol_version,
+        services,
+        user_agent,
+        start_height,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, TcpListener};
+    use std::thread::{spawn, JoinHandle};
+
+    use super::*;
+    use crate::test_utils::closed_addr;
+
+    const MAINNET_MAGIC: [u8; 4] = [0xf9, 0xbe, 0xb4, 0xd9];
+
+    /// Spawn a fake peer answering the handshake with the given magic bytes.
+    fn fake_peer(magic: [u8; 4]) -> (SocketAddr, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handle = spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut commands = Vec::new();
+            let (command, payload) = read_message(&mut stream, MAINNET_MAGIC).unwrap();
+            assert_eq!(parse_version(&payload).unwrap().user_agent, P2P_USER_AGENT);
+            commands.push(command);
+
+            let mut version = version_payload(addr);
+            version.truncate(80);
+            version[4..12].copy_from_slice(&0x409u64.to_le_bytes());
+            version.extend_from_slice(&[0x10]);
+            version.extend_from_slice(b"/Satoshi:27.0.0/");
+            version.extend_from_slice(&840_000i32.to_le_bytes());
+            version.push(1);
+            stream
+                .write_all(&encode_message(magic, "version", &version))
+                .unwrap();
+            stream
+                .write_all(&encode_message(magic, "wtxidrelay", &[]))
+                .unwrap();
+            stream
+                .write_all(&encode_message(magic, "verack", &[]))
+                .unwrap();
+
+            if let Ok((command, _)) = read_message(&mut stream, MAINNET_MAGIC) {
+                commands.push(command);
+            }
+            commands
+        });
+        (addr, handle)
+    }
+
+    #[test]
+    fn bitcoin_p2p_target_from_str() {
+        // Expectency: BitcoinP2pTargets are parsed from "host:port" for mainnet.
+        let target = BitcoinP2pTarget::from_str("localhost:8333").unwrap();
+        assert_eq!(target.get_fqhn(), "localhost");
+        assert_eq!(target.get_portnumber(), &8333);
+        assert_eq!(target.get_magic(), &MAINNET_MAGIC);
+        assert_eq!(target.get_connect_timeout(), &DEFAULT_TCP_CONNECT_TIMEOUT);
+        assert_eq!(target.get_resolve_policy(), &ResolvePolicy::Agnostic);
+        assert_eq!(target.get_id(), "localhost:8333");
+        assert!(BitcoinP2pTarget::from_str("localhost").is_err());
+    }
+
+    #[test]
+    fn encode_read_message() {
+        // Expectency: Encoded messages are read back, checksums are verified.
+        let message = encode_message(MAINNET_MAGIC, "ping", &[1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(&message[20..24], &checksum(&[1, 2, 3, 4, 5, 6, 7, 8]));
+        let (command, payload) = read_message(&mut message.as_slice(), MAINNET_MAGIC).unwrap();
+        assert_eq!(command, "ping");
+        assert_eq!(payload, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+
+        // Note: The checksum of an empty payload is well known
+        assert_eq!(checksum(&[]), [0x5d, 0xf6, 0xe0, 0xe2]);
+
+        let mut corrupted = message.clone();
+        corrupted[24] = 0;
+        assert!(read_message(&mut corrupted.as_slice(), MAINNET_MAGIC).is_err());
+    }
+
+    #[test]
+    fn bitcoin_p2p_target_check_availability() {
+        // Expectency: A completed handshake means available and reports the peers version.
+        let (addr, peer) = fake_peer(MAINNET_MAGIC);
+        let target = BitcoinP2pTarget::from(addr);
+        assert_eq!(target.get_peer_info(), None);
+        assert_eq!(target.check_availability().unwrap(), Status::Available);
+        assert_eq!(
+            target.get_peer_info(),
+            Some(PeerInfo {
+                protocol_version: P2P_PROTOCOL_VERSION,
+                services: 0x409,
+                user_agent: String::from("/Satoshi:27.0.0/"),
+                start_height: 840_000,
+            })
+        );
+        assert_eq!(peer.join().unwrap(), vec!["version", "verack"]);
+    }
+
+    #[test]
+    fn bitcoin_p2p_target_check_wrong_network() {
+        // Expectency: Peers of another network are not available.
+        let (addr, peer) = fake_peer(Network::Testnet.p2p_magic().unwrap());
+        let target = BitcoinP2pTarget::from(addr);
+        assert_eq!(target.check_availability().unwrap(), Status::NotAvailable);
+        assert_eq!(target.get_peer_info(), None);
+        peer.join().unwrap();
+    }
+
+    #[test]
+    fn bitcoin_p2p_target_check_unavailability() {
+        // Expectency: Closed ports and silent peers are not available.
+        let target = BitcoinP2pTarget::from(closed_addr());
+        assert_eq!(target.check_availability().unwrap(), Status::NotAvailable);
+
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let target = BitcoinP2pTarget::from(listener.local_addr().unwrap())
+            .set_connect_timeout(Duration::from_millis(100));
+        assert_eq!(target.check_availability().unwrap(), Status::NotAvailable);
+    }
+}
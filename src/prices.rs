@@ -0,0 +1,231 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Module containing bitcoin price types and sats/fiat conversion helpers.
+
+// Imports
+use super::ParseParameterError;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt::{self};
+use std::str::FromStr;
+
+/// Number of satoshis per bitcoin
+pub const SATS_PER_BTC: u64 = 100_000_000;
+
+/// Fiat currencies quoted by mempool.space.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub enum Currency {
+    /// US Dollar
+    Usd,
+    /// Euro
+    Eur,
+    /// British Pound
+    Gbp,
+    /// Canadian Dollar
+    Cad,
+    /// Swiss Franc
+    Chf,
+    /// Australian Dollar
+    Aud,
+    /// Japanese Yen
+    Jpy,
+}
+
+impl Currency {
+    /// All quoted currencies
+    pub const ALL: [Currency; 7] = [
+        Currency::Usd,
+        Currency::Eur,
+        Currency::Gbp,
+        Currency::Cad,
+        Currency::Chf,
+        Currency::Aud,
+        Currency::Jpy,
+    ];
+
+    /// Get the ISO 4217 code of this [Currency] as used by the API, e.g. "USD".
+    pub fn code(&self) -> &'static str {
+        match self {
+            Currency::Usd => "USD",
+            Currency::Eur => "EUR",
+            Currency::Gbp => "GBP",
+            Currency::Cad => "CAD",
+            Currency::Chf => "CHF",
+            Currency::Aud => "AUD",
+            Currency::Jpy => "JPY",
+        }
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.code())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = ParseParameterError;
+
+    fn from_str(s: &str) -> Result<Currency, Self::Err> {
+        Currency::ALL
+            .into_iter()
+            .find(|currency| currency.code().eq_ignore_ascii_case(s))
+            .ok_or(ParseParameterError::from("Unknown currency"))
+    }
+}
+
+/// Bitcoin prices at a point in time as returned by `GET /v1/prices`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(from = "RawPrices")]
+pub struct Prices {
+    /// Time of the quote in seconds since the unix epoch
+    pub time: u64,
+    /// Price of one bitcoin per quoted [Currency]
+    rates: HashMap<Currency, f64>,
+}
+
+impl Prices {
+    /// Get the price of one bitcoin in given [Currency], if quoted.
+    pub fn get(&self, currency: Currency) -> Option<f64> {
+        self.rates.get(&currency).copied()
+    }
+
+    /// Get the value of given satoshis in given [Currency], if quoted.
+    pub fn sats_to_fiat(&self, sats: u64, currency: Currency) -> Option<f64> {
+        self.get(currency).map(|price| sats_to_fiat(sats, price))
+    }
+
+    /// Get the satoshis worth given amount of [Currency], if quoted.
+    pub fn fiat_to_sats(&self, amount: f64, currency: Currency) -> Option<u64> {
+        self.get(currency).map(|price| fiat_to_sats(amount, price))
+    }
+}
+
+/// Wire format of [Prices]. Currencies unknown to [Currency] are skipped.
+#[derive(Deserialize)]
+struct RawPrices {
+    time: u64,
+    #[serde(flatten)]
+    rates: HashMap<String, serde_json::Value>,
+}
+
+impl From<RawPrices> for Prices {
+    fn from(raw: RawPrices) -> Self {
+        let rates = raw
+            .rates
+            .into_iter()
+            .filter_map(|(code, price)| Some((Currency::from_str(&code).ok()?, price.as_f64()?)))
+            .collect();
+        Prices {
+            time: raw.time,
+            rates,
+        }
+    }
+}
+
+/// Historical prices as returned by `GET /v1/historical-price`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoricalPrice {
+    /// Quotes closest to the requested timestamp
+    pub prices: Vec<Prices>,
+    /// Exchange rates between USD and other currencies, e.g. "USDEUR"
+    pub exchange_rates: HashMap<String, f64>,
+}
+
+impl HistoricalPrice {
+    /// Get the price of one bitcoin in given [Currency] of the first quote, if quoted.
+    pub fn get(&self, currency: Currency) -> Option<f64> {
+        self.prices.first().and_then(|prices| prices.get(currency))
+    }
+}
+
+/// Convert satoshis into fiat given the price of one bitcoin.
+///
+/// # Example
+/// ```
+/// # use mempool_space::prices::sats_to_fiat;
+///
+/// assert_eq!(sats_to_fiat(50_000_000, 60_000.0), 30_000.0);
+/// ```
+pub fn sats_to_fiat(sats: u64, price: f64) -> f64 {
+    sats as f64 * price / SATS_PER_BTC as f64
+}
+
+/// Convert fiat into satoshis given the price of one bitcoin, rounded to the nearest satoshi.
+///
+/// # Example
+/// ```
+/// # use mempool_space::prices::fiat_to_sats;
+///
+/// assert_eq!(fiat_to_sats(30_000.0, 60_000.0), 50_000_000);
+/// ```
+pub fn fiat_to_sats(amount: f64, price: f64) -> u64 {
+    (amount * SATS_PER_BTC as f64 / price).round() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn currency_from_str() {
+        // Expectency: Currency codes are parsed case insensitive and roundtrip via Display.
+        assert_eq!(Currency::from_str("usd").unwrap(), Currency::Usd);
+        assert_eq!(Currency::from_str("JPY").unwrap(), Currency::Jpy);
+        for currency in Currency::ALL {
+            assert_eq!(Currency::from_str(&currency.to_string()).unwrap(), currency);
+        }
+        assert_eq!(
+            format!("{}", Currency::from_str("BTC").unwrap_err()),
+            "Unknown currency"
+        );
+    }
+
+    #[test]
+    fn prices_deserialize() {
+        // Expectency: Quoted currencies must be accessible via Currency, unknown currencies
+        //             are skipped.
+        let json = r#"{
+            "time": 1703252411,
+            "USD": 43753,
+            "EUR": 40545,
+            "GBP": 35086,
+            "CAD": 58680,
+            "CHF": 37659,
+            "AUD": 64992,
+            "JPY": 6263589,
+            "BRL": 210000
+        }"#;
+        let prices: Prices = serde_json::from_str(json).unwrap();
+        assert_eq!(prices.time, 1703252411);
+        assert_eq!(prices.get(Currency::Usd), Some(43753.0));
+        assert_eq!(prices.get(Currency::Jpy), Some(6263589.0));
+        assert_eq!(prices.rates.len(), 7);
+    }
+
+    #[test]
+    fn historical_price_deserialize() {
+        // Expectency: Historical quotes may contain a subset of all currencies.
+        let json = r#"{
+            "prices": [{"time": 1499904000, "EUR": 1964, "USD": 2254.9}],
+            "exchangeRates": {"USDEUR": 0.92, "USDJPY": 151.2}
+        }"#;
+        let historical: HistoricalPrice = serde_json::from_str(json).unwrap();
+        assert_eq!(historical.get(Currency::Usd), Some(2254.9));
+        assert_eq!(historical.get(Currency::Gbp), None);
+        assert_eq!(historical.exchange_rates["USDEUR"], 0.92);
+    }
+
+    #[test]
+    fn prices_conversion() {
+        // Expectency: Conversions use the quoted price and fail for unquoted currencies.
+        let prices: Prices = serde_json::from_str(r#"{"time": 0, "EUR": 40000}"#).unwrap();
+        assert_eq!(prices.sats_to_fiat(250_000, Currency::Eur), Some(100.0));
+        assert_eq!(prices.fiat_to_sats(100.0, Currency::Eur), Some(250_000));
+        assert_eq!(prices.sats_to_fiat(250_000, Currency::Usd), None);
+        assert_eq!(fiat_to_sats(0.01, 43753.0), 23);
+    }
+}
@@ -0,0 +1,416 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Module containing client-side rate limiting and retry policies.
+
+// Imports
+use super::ParseParameterError;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// Documentation imports
+#[cfg(doc)]
+use super::{MempoolClient, MempoolError};
+
+/// Default [Duration] of the first retry of a [RetryPolicy]
+pub const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Default upper bound of the [Duration] between two retries of a [RetryPolicy]
+pub const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Default upper bound of the [Duration] a [RetryPolicy] waits for an announced Retry-After
+pub const DEFAULT_MAX_RETRY_AFTER: Duration = Duration::from_secs(300);
+
+/// Token bucket limiting the request rate of one or more [MempoolClient]s.
+///
+/// The bucket holds up to `burst` tokens and is refilled with `requests_per_second` tokens
+/// per second. Each request takes one token, waiting for the bucket to refill if empty.
+///
+/// # Example
+/// ```
+/// # use std::sync::Arc;
+/// # use mempool_space::{MempoolClient, RateLimiter};
+///
+/// // Allow 5 requests at once, afterwards one request every 500ms
+/// let limiter = Arc::new(RateLimiter::new(2.0, 5).unwrap());
+/// let client = MempoolClient::default().set_rate_limiter(limiter);
+/// ```
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// Number of tokens refilled per second.
+    requests_per_second: f64,
+    /// Maximum number of tokens in the bucket.
+    burst: u32,
+    /// Current number of tokens, negative if reserved ahead, and the time of the last refill.
+    state: Mutex<(f64, Instant)>,
+}
+
+impl RateLimiter {
+    /// Construct a [RateLimiter] with a full bucket.
+    ///
+    /// # Arguments
+    /// * requests_per_second: the sustained request rate, must be finite and positive.
+    /// * burst: the number of requests allowed at once. 0 is treated as 1.
+    ///
+    /// # Returns
+    /// * On success, the [RateLimiter].
+    /// * On failure, a [ParseParameterError] if requests_per_second is not finite and positive.
+    pub fn new(requests_per_second: f64, burst: u32) -> Result<Self, ParseParameterError> {
+        if !(requests_per_second.is_finite() && requests_per_second > 0.0) {
+            return Err(ParseParameterError::from(
+                "Requests per second must be finite and positive",
+            ));
+        }
+        let burst = burst.max(1);
+        Ok(RateLimiter {
+            requests_per_second,
+            burst,
+            state: Mutex::new((burst as f64, Instant::now())),
+        })
+    }
+
+    /// Get the number of tokens refilled per second.
+    pub fn get_requests_per_second(&self) -> f64 {
+        self.requests_per_second
+    }
+
+    /// Get the maximum number of requests allowed at once.
+    pub fn get_burst(&self) -> u32 {
+        self.burst
+    }
+
+    /// Take a token from the bucket.
+    ///
+    /// # Returns
+    /// The [Duration] to wait before performing the request, [Duration::ZERO] if a token
+    /// was available.
+    ///
+    /// # Notes
+    /// If the bucket is empty, a future token is reserved, so concurrent callers are
+    /// spaced out instead of waiting for the same token.
+    pub fn reserve(&self) -> Duration {
+        let mut state = self.state.lock().unwrap();
+        let (tokens, last_refill) = &mut *state;
+        let now = Instant::now();
+        let refill = now.duration_since(*last_refill).as_secs_f64() * self.requests_per_second;
+        *tokens = (*tokens + refill).min(self.burst as f64) - 1.0;
+        *last_refill = now;
+
+        if *tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-*tokens / self.requests_per_second)
+        }
+    }
+}
+
+/// Policy retrying requests rejected with [MempoolError::RateLimited].
+///
+/// Retries wait for the duration announced via the Retry-After header, given either in
+/// seconds or as HTTP date, bounded by the maximum Retry-After. Without it, the delay
+/// doubles with each retry starting at the base delay, bounded by the maximum delay. A random
+/// jitter spreads out retries of concurrent clients.
+///
+/// # Example
+/// ```
+/// # use std::time::Duration;
+/// # use mempool_space::{MempoolClient, RetryPolicy};
+///
+/// let retry_policy = RetryPolicy::new(3).set_base_delay(Duration::from_secs(1));
+/// let client = MempoolClient::default().set_retry_policy(retry_policy);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of retries per request.
+    max_retries: u32,
+    /// [Duration] of the first retry without Retry-After header.
+    base_delay: Duration,
+    /// Upper bound of the [Duration] between two retries without Retry-After header.
+    max_delay: Duration,
+    /// Upper bound of the [Duration] between two retries with Retry-After header.
+    max_retry_after: Duration,
+}
+
+impl RetryPolicy {
+    /// Construct a [RetryPolicy] using [DEFAULT_BASE_DELAY], [DEFAULT_MAX_DELAY] and
+    /// [DEFAULT_MAX_RETRY_AFTER].
+    ///
+    /// # Arguments
+    /// * max_retries: maximum number of retries per request.
+    pub fn new(max_retries: u32) -> Self {
+        RetryPolicy {
+            max_retries,
+            base_delay: DEFAULT_BASE_DELAY,
+            max_delay: DEFAULT_MAX_DELAY,
+            max_retry_after: DEFAULT_MAX_RETRY_AFTER,
+        }
+    }
+
+    /// Set a new [Duration] of the first retry.
+    pub fn set_base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Set a new upper bound of the [Duration] between two retries.
+    pub fn set_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Set a new upper bound of the [Duration] waited for an announced Retry-After.
+    pub fn set_max_retry_after(mut self, max_retry_after: Duration) -> Self {
+        self.max_retry_after = max_retry_after;
+        self
+    }
+
+    /// Get the maximum number of retries per request.
+    pub fn get_max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    /// Get a reference to the [Duration] of the first retry.
+    pub fn get_base_delay(&self) -> &Duration {
+        &self.base_delay
+    }
+
+    /// Get a reference to the upper bound of the [Duration] between two retries.
+    pub fn get_max_delay(&self) -> &Duration {
+        &self.max_delay
+    }
+
+    /// Get a reference to the upper bound of the [Duration] waited for an announced Retry-After.
+    pub fn get_max_retry_after(&self) -> &Duration {
+        &self.max_retry_after
+    }
+
+    /// Calculate the [Duration] to wait before the given retry.
+    ///
+    /// # Arguments
+    /// * retry: number of the retry, starting at 0.
+    /// * retry_after: [Duration] announced by the server, if any.
+    ///
+    /// # Returns
+    /// * None, if the maximum number of retries is exhausted.
+    /// * The announced duration plus a jitter of up to 10%, bounded by the maximum
+    ///   Retry-After, if announced.
+    /// * Otherwise, a random duration between half and all of the exponential backoff.
+    pub fn get_delay(&self, retry: u32, retry_after: Option<Duration>) -> Option<Duration> {
+        if retry >= self.max_retries {
+            return None;
+        }
+        let delay = match retry_after {
+            Some(retry_after) => {
+                let retry_after = retry_after.min(self.max_retry_after);
+                let jitter = retry_after.mul_f64(0.1 * random_fraction());
+                (retry_after + jitter).min(self.max_retry_after)
+            }
+            None => {
+                let backoff = self
+                    .base_delay
+                    .saturating_mul(2u32.saturating_pow(retry))
+                    .min(self.max_delay);
+                backoff / 2 + (backoff / 2).mul_f64(random_fraction())
+            }
+        };
+        Some(delay)
+    }
+}
+
+/// Parse the value of a Retry-After header.
+///
+/// # Arguments
+/// * value: either a number of seconds, e.g. "120", or an HTTP date in the IMF-fixdate
+///   format, e.g. "Wed, 21 Oct 2015 07:28:00 GMT".
+///
+/// # Returns
+/// The [Duration] to wait, zero for dates in the past. None if the value is malformed.
+///
+/// # Notes
+/// The obsolete RFC 850 and asctime date formats are not supported.
+pub(crate) fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = parse_http_date(value)?;
+    Some(date.duration_since(SystemTime::now()).unwrap_or_default())
+}
+
+/// Parse an HTTP date in the IMF-fixdate format, e.g. "Wed, 21 Oct 2015 07:28:00 GMT".
+fn parse_http_date(value: &str) -> Option<SystemTime> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let (_, date) = value.split_once(", ")?;
+    let parts: Vec<&str> = date.split(' ').collect();
+    let [day, month, year, time, "GMT"] = parts.as_slice() else {
+        return None;
+    };
+    let day: u64 = day.parse().ok()?;
+    let month = MONTHS.iter().position(|name| name == month)? as u64 + 1;
+    let year: u64 = year.parse().ok()?;
+    let time: Vec<u64> = time
+        .split(':')
+        .map(|part| part.parse().ok())
+        .collect::<Option<_>>()?;
+    let [hours, minutes, seconds] = time.as_slice() else {
+        return None;
+    };
+    if !(1..=31).contains(&day) || year < 1970 || *hours > 23 || *minutes > 59 || *seconds > 60 {
+        return None;
+    }
+
+    // Days since the unix epoch of the civil date, years starting in March
+    let (year, month) = match month {
+        1 | 2 => (year - 1, month + 9),
+        _ => (year, month - 3),
+    };
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era
+        .checked_mul(146_097)?
+        .checked_add(day_of_era)?
+        .checked_sub(719_468)?;
+
+    let secs = days
+        .checked_mul(86_400)?
+        .checked_add(hours * 3_600 + minutes * 60 + seconds)?;
+    UNIX_EPOCH.checked_add(Duration::from_secs(secs))
+}
+
+/// Get a random number within [0, 1), seeded by the randomly keyed hasher of the standard library.
+fn random_fraction() -> f64 {
+    let random = RandomState::new().build_hasher().finish();
+    (random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limiter_burst() {
+        // Expectency: Requests within the burst pass immediately, afterwards each request
+        //             reserves the next free token.
+        let limiter = RateLimiter::new(10.0, 2).unwrap();
+        assert_eq!(limiter.reserve(), Duration::ZERO);
+        assert_eq!(limiter.reserve(), Duration::ZERO);
+
+        let delay = limiter.reserve();
+        assert!(delay > Duration::from_millis(90) && delay <= Duration::from_millis(100));
+        let delay = limiter.reserve();
+        assert!(delay > Duration::from_millis(190) && delay <= Duration::from_millis(200));
+    }
+
+    #[test]
+    fn rate_limiter_refill() {
+        // Expectency: Tokens are refilled over time up to the burst size.
+        let limiter = RateLimiter::new(1000.0, 0).unwrap();
+        assert_eq!(limiter.get_burst(), 1);
+        assert_eq!(limiter.reserve(), Duration::ZERO);
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(limiter.reserve(), Duration::ZERO);
+        assert!(limiter.reserve() > Duration::ZERO);
+    }
+
+    #[test]
+    fn rate_limiter_invalid_rate() {
+        // Expectency: Rates that are not finite and positive are rejected.
+        for requests_per_second in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(RateLimiter::new(requests_per_second, 1).is_err());
+        }
+    }
+
+    #[test]
+    fn retry_policy_backoff() {
+        // Expectency: The delay doubles with each retry within the jitter bounds, is capped
+        //             by the maximum delay and ends after the maximum number of retries.
+        let policy = RetryPolicy::new(12)
+            .set_base_delay(Duration::from_millis(100))
+            .set_max_delay(Duration::from_secs(1));
+        for (retry, backoff) in [
+            (0, 100),
+            (1, 200),
+            (2, 400),
+            (3, 800),
+            (4, 1000),
+            (11, 1000),
+        ] {
+            let delay = policy.get_delay(retry, None).unwrap();
+            assert!(delay >= Duration::from_millis(backoff / 2), "{}", retry);
+            assert!(delay <= Duration::from_millis(backoff), "{}", retry);
+        }
+        assert_eq!(policy.get_delay(12, None), None);
+    }
+
+    #[test]
+    fn retry_policy_retry_after() {
+        // Expectency: An announced Retry-After duration is respected with a small jitter.
+        let policy = RetryPolicy::new(1);
+        let delay = policy.get_delay(0, Some(Duration::from_secs(10))).unwrap();
+        assert!(delay >= Duration::from_secs(10) && delay <= Duration::from_secs(11));
+        assert_eq!(policy.get_delay(1, Some(Duration::from_secs(10))), None);
+    }
+
+    #[test]
+    fn retry_after_parsing() {
+        // Expectency: Retry-After values are parsed from seconds and HTTP dates, dates in
+        //             the past mean no delay.
+        assert_eq!(parse_retry_after(" 12 "), Some(Duration::from_secs(12)));
+        assert_eq!(
+            parse_http_date("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(UNIX_EPOCH + Duration::from_secs(1_445_412_480))
+        );
+        assert_eq!(
+            parse_http_date("Thu, 29 Feb 2024 00:00:00 GMT"),
+            Some(UNIX_EPOCH + Duration::from_secs(1_709_164_800))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+
+        let in_a_minute = parse_retry_after("Fri, 31 Dec 9999 23:59:59 GMT").unwrap();
+        assert!(in_a_minute > Duration::from_secs(60));
+
+        assert_eq!(parse_retry_after("soon"), None);
+        assert_eq!(parse_retry_after("Wed, 21 Foo 2015 07:28:00 GMT"), None);
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28 GMT"), None);
+        assert_eq!(parse_retry_after("Wednesday, 21-Oct-15 07:28:00 GMT"), None);
+    }
+
+    #[test]
+    fn retry_after_overflow() {
+        // Expectency: Dates too far in the future to be represented are rejected instead of
+        //             overflowing, announced delays are bounded by the maximum Retry-After.
+        assert_eq!(
+            parse_retry_after("Mon, 01 Jan 300000000000 00:00:00 GMT"),
+            None
+        );
+        assert_eq!(
+            parse_retry_after("Mon, 01 Jan 18446744073709551615 00:00:00 GMT"),
+            None
+        );
+
+        let policy = RetryPolicy::new(1).set_max_retry_after(Duration::from_secs(60));
+        assert_eq!(policy.get_max_retry_after(), &Duration::from_secs(60));
+        let delay = policy.get_delay(0, Some(Duration::MAX)).unwrap();
+        assert_eq!(delay, Duration::from_secs(60));
+    }
+
+    #[test]
+    fn random_fraction_range() {
+        // Expectency: Random fractions are within [0, 1) and not constant.
+        let fractions: Vec<f64> = (0..32).map(|_| random_fraction()).collect();
+        assert!(fractions
+            .iter()
+            .all(|fraction| (0.0..1.0).contains(fraction)));
+        assert!(fractions.iter().any(|fraction| *fraction != fractions[0]));
+    }
+}
@@ -18,7 +18,7 @@ use super::{IcmpTarget, TcpTarget};
 
 /// A ResolvePolicy allows control over IP address resolution of network targets
 /// like [IcmpTarget] and [TcpTarget].
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone, Copy)]
 pub enum ResolvePolicy {
     /// Resolve use all IP address versions
     Agnostic,
@@ -43,7 +43,7 @@ impl ResolvePolicy {
     /// # Example
     /// ```
     /// # use std::net::{IpAddr, Ipv4Addr};
-    /// # use reachable::ResolvePolicy;
+    /// # use mempool_space::ResolvePolicy;
     ///
     /// // FQHN was resolved
     /// assert_eq!(
@@ -0,0 +1,466 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Module containing a live subscription client for the mempool.space WebSocket API.
+//!
+//! # Notes
+//! Requires crate to be configured with feature "websocket".
+
+// Imports
+use super::block::Block;
+use super::difficulty::DifficultyAdjustment;
+use super::error::MempoolError;
+use super::fees::{MempoolBlock, RecommendedFees};
+use super::mempool::RecentTransaction;
+use super::network::Network;
+use super::prices::Prices;
+use super::transaction::Transaction;
+use futures::{SinkExt, Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Map, Value};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tokio::task::JoinHandle;
+use tokio::time::sleep;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+
+/// Default [Duration] to wait before reconnecting a [MempoolStream]
+pub const DEFAULT_RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Number of events buffered before the connection waits for the consumer
+const EVENT_BUFFER_SIZE: usize = 64;
+
+/// Alias for an event or failure yielded by a [MempoolStream]
+type EventResult = Result<MempoolEvent, MempoolError>;
+
+/// Data a [MempoolStream] subscribes to.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Subscription {
+    /// New blocks, `want` action "blocks"
+    Blocks,
+    /// Mempool statistics, fees and difficulty adjustment, `want` action "stats"
+    Stats,
+    /// Projected mempool blocks, `want` action "mempool-blocks"
+    MempoolBlocks,
+    /// Mempool chart data of the last 2 hours, `want` action "live-2h-chart"
+    Live2hChart,
+    /// Transactions of the given address, `track-address` action
+    TrackAddress(String),
+    /// Confirmation of the given txid, `track-tx` action
+    TrackTx(String),
+}
+
+/// Event received by a [MempoolStream].
+#[derive(Debug, Clone, PartialEq)]
+pub enum MempoolEvent {
+    /// A new block was mined
+    Block(Block),
+    /// The newest blocks, sent on subscription to [Subscription::Blocks]
+    Blocks(Vec<Block>),
+    /// Updated projection of the next mempool blocks
+    MempoolBlocks(Vec<MempoolBlock>),
+    /// Updated recommended feerates
+    Fees(RecommendedFees),
+    /// Updated progress of the current difficulty epoch
+    DifficultyAdjustment(DifficultyAdjustment),
+    /// Rate of virtual bytes entering the mempool per second
+    VBytesPerSecond(f64),
+    /// Transactions recently added to the mempool
+    Transactions(Vec<RecentTransaction>),
+    /// New mempool transactions of a tracked address
+    AddressTransactions(Vec<Transaction>),
+    /// Transactions of a tracked address confirmed by a new block
+    AddressBlockTransactions(Vec<Transaction>),
+    /// A tracked transaction confirmed. Contains the txid, if sent by the server.
+    TxConfirmed(Option<String>),
+    /// Updated bitcoin prices
+    Conversions(Prices),
+    /// The connection was re-established and all subscriptions were sent again.
+    /// Events sent in between may have been missed.
+    Reconnected,
+    /// Any other data identified by its key, e.g. "live-2h-chart"
+    Other(String, Value),
+}
+
+/// Live subscription to the mempool.space WebSocket API.
+///
+/// Yields [MempoolEvent]s for all configured [Subscription]s. The connection is established on
+/// first poll. Whenever it fails or is closed by the server, the stream reconnects after the
+/// configured delay and subscribes again. Dropping the stream closes the connection.
+///
+/// # Notes
+/// Connection failures are yielded as [MempoolError] without ending the stream.
+/// Must be polled within a tokio runtime.
+///
+/// # Example
+/// ```no_run
+/// # use futures::StreamExt;
+/// # use mempool_space::{MempoolEvent, MempoolStream, Network, Subscription};
+///
+/// # async fn example() {
+/// let mut stream = MempoolStream::from(Network::Mainnet)
+///     .subscribe(Subscription::Blocks)
+///     .subscribe(Subscription::MempoolBlocks);
+///
+/// while let Some(event) = stream.next().await {
+///     if let Ok(MempoolEvent::Block(block)) = event {
+///         println!("New block: {}", block.height);
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct MempoolStream {
+    /// Url of the WebSocket API, e.g. "wss://mempool.space/api/v1/ws".
+    url: String,
+    /// Subscriptions sent on each connect.
+    subscriptions: Vec<Subscription>,
+    /// [Duration] to wait before reconnecting.
+    reconnect_delay: Duration,
+    /// Connection task and the channel receiving its events, once started.
+    worker: Option<(JoinHandle<()>, Receiver<EventResult>)>,
+}
+
+impl MempoolStream {
+    /// Construct a [MempoolStream] without any subscription.
+    ///
+    /// # Arguments
+    /// * url: url of the WebSocket API, e.g. "wss://mempool.space/api/v1/ws".
+    ///
+    /// # Notes
+    /// For more convenience use the implementation of trait "From" for [Network].
+    pub fn new(url: &str) -> Self {
+        MempoolStream {
+            url: String::from(url),
+            subscriptions: Vec::new(),
+            reconnect_delay: DEFAULT_RECONNECT_DELAY,
+            worker: None,
+        }
+    }
+
+    /// Add a [Subscription] sent on each connect.
+    pub fn subscribe(mut self, subscription: Subscription) -> Self {
+        self.subscriptions.push(subscription);
+        self
+    }
+
+    /// Set a new [Duration] to wait before reconnecting.
+    pub fn set_reconnect_delay(mut self, reconnect_delay: Duration) -> Self {
+        self.reconnect_delay = reconnect_delay;
+        self
+    }
+
+    /// Get a reference to the url in use.
+    pub fn get_url(&self) -> &str {
+        &self.url
+    }
+
+    /// Get a reference to all subscriptions.
+    pub fn get_subscriptions(&self) -> &[Subscription] {
+        &self.subscriptions
+    }
+
+    /// Get a reference to the reconnect delay in use.
+    pub fn get_reconnect_delay(&self) -> &Duration {
+        &self.reconnect_delay
+    }
+}
+
+impl From<Network> for MempoolStream {
+    fn from(network: Network) -> Self {
+        MempoolStream::new(&network.websocket_url())
+    }
+}
+
+impl Stream for MempoolStream {
+    type Item = Result<MempoolEvent, MempoolError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let (_, receiver) = this.worker.get_or_insert_with(|| {
+            let (sender, receiver) = channel(EVENT_BUFFER_SIZE);
+            let task = tokio::spawn(run(
+                this.url.clone(),
+                subscription_messages(&this.subscriptions),
+                this.reconnect_delay,
+                sender,
+            ));
+            (task, receiver)
+        });
+        receiver.poll_recv(cx)
+    }
+}
+
+impl Drop for MempoolStream {
+    fn drop(&mut self) {
+        if let Some((task, _)) = self.worker.take() {
+            task.abort();
+        }
+    }
+}
+
+/// Maintain the connection to given url, forwarding all received events to given sender
+/// until it is closed.
+async fn run(
+    url: String,
+    messages: Vec<String>,
+    reconnect_delay: Duration,
+    sender: Sender<EventResult>,
+) {
+    let mut reconnect = false;
+    loop {
+        let events = match connect_async(url.as_str()).await {
+            Ok((mut socket, _)) => {
+                if reconnect && sender.send(Ok(MempoolEvent::Reconnected)).await.is_err() {
+                    return;
+                }
+                reconnect = true;
+
+                // Subscribe, then forward events until the connection ends
+                let mut result = Ok(());
+                for message in &messages {
+                    result = result.and(socket.send(Message::Text(message.clone())).await);
+                }
+                if let Err(error) = result {
+                    vec![Err(MempoolError::from(error))]
+                } else {
+                    loop {
+                        let message = tokio::select! {
+                            message = socket.next() => message,
+                            _ = sender.closed() => return,
+                        };
+                        let events = match message {
+                            Some(Ok(Message::Text(text))) => parse_events(&text),
+                            Some(Ok(Message::Close(_))) | None => break Vec::new(),
+                            Some(Ok(_)) => continue,
+                            Some(Err(error)) => break vec![Err(MempoolError::from(error))],
+                        };
+                        for event in events {
+                            if sender.send(event).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                }
+            }
+            Err(error) => vec![Err(MempoolError::from(error))],
+        };
+
+        for event in events {
+            if sender.send(event).await.is_err() {
+                return;
+            }
+        }
+        tokio::select! {
+            _ = sleep(reconnect_delay) => {},
+            _ = sender.closed() => return,
+        }
+    }
+}
+
+/// Build the messages announcing given subscriptions to the server.
+fn subscription_messages(subscriptions: &[Subscription]) -> Vec<String> {
+    let mut wants = Vec::new();
+    let mut addresses = Vec::new();
+    let mut messages = Vec::new();
+    for subscription in subscriptions {
+        match subscription {
+            Subscription::Blocks => wants.push("blocks"),
+            Subscription::Stats => wants.push("stats"),
+            Subscription::MempoolBlocks => wants.push("mempool-blocks"),
+            Subscription::Live2hChart => wants.push("live-2h-chart"),
+            Subscription::TrackAddress(address) => addresses.push(address),
+            Subscription::TrackTx(txid) => messages.push(json!({ "track-tx": txid })),
+        }
+    }
+
+    if !wants.is_empty() {
+        messages.insert(0, json!({ "action": "want", "data": wants }));
+    }
+    match addresses.as_slice() {
+        [] => {}
+        [address] => messages.push(json!({ "track-address": address })),
+        addresses => messages.push(json!({ "track-addresses": addresses })),
+    }
+    messages.iter().map(Value::to_string).collect()
+}
+
+/// Parse a message of the server into one event per contained key.
+fn parse_events(text: &str) -> Vec<EventResult> {
+    let map: Map<String, Value> = match serde_json::from_str(text) {
+        Ok(map) => map,
+        Err(error) => return vec![Err(MempoolError::from(error))],
+    };
+    map.into_iter()
+        .map(|(key, value)| parse_event(key, value))
+        .collect()
+}
+
+/// Parse the value of a single key of a server message into a [MempoolEvent].
+fn parse_event(key: String, value: Value) -> EventResult {
+    fn typed<T: DeserializeOwned>(value: Value) -> Result<T, MempoolError> {
+        Ok(serde_json::from_value(value)?)
+    }
+
+    match key.as_str() {
+        "block" => typed(value).map(MempoolEvent::Block),
+        "blocks" => typed(value).map(MempoolEvent::Blocks),
+        "mempool-blocks" => typed(value).map(MempoolEvent::MempoolBlocks),
+        "fees" => typed(value).map(MempoolEvent::Fees),
+        "da" => typed(value).map(MempoolEvent::DifficultyAdjustment),
+        "vBytesPerSecond" => typed(value).map(MempoolEvent::VBytesPerSecond),
+        "transactions" => typed(value).map(MempoolEvent::Transactions),
+        "address-transactions" => typed(value).map(MempoolEvent::AddressTransactions),
+        "block-transactions" => typed(value).map(MempoolEvent::AddressBlockTransactions),
+        "txConfirmed" => Ok(MempoolEvent::TxConfirmed(value.as_str().map(String::from))),
+        "conversions" => typed(value).map(MempoolEvent::Conversions),
+        _ => Ok(MempoolEvent::Other(key, value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prices::Currency;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_async;
+
+    #[test]
+    fn mempool_stream_new() {
+        // Expectency: A stream constructed from a network must use its WebSocket url.
+        let stream = MempoolStream::from(Network::Signet)
+            .subscribe(Subscription::Blocks)
+            .set_reconnect_delay(Duration::from_millis(10));
+        assert_eq!(stream.get_url(), "wss://mempool.space/signet/api/v1/ws");
+        assert_eq!(stream.get_subscriptions(), &[Subscription::Blocks]);
+        assert_eq!(stream.get_reconnect_delay(), &Duration::from_millis(10));
+    }
+
+    #[test]
+    fn subscription_messages_combined() {
+        // Expectency: All want actions are combined into a single message, multiple
+        //             addresses are tracked at once.
+        let messages = subscription_messages(&[
+            Subscription::Blocks,
+            Subscription::TrackTx(String::from("a1")),
+            Subscription::MempoolBlocks,
+            Subscription::TrackAddress(String::from("bc1qa")),
+            Subscription::TrackAddress(String::from("bc1qb")),
+        ]);
+        assert_eq!(
+            messages,
+            vec![
+                r#"{"action":"want","data":["blocks","mempool-blocks"]}"#,
+                r#"{"track-tx":"a1"}"#,
+                r#"{"track-addresses":["bc1qa","bc1qb"]}"#,
+            ]
+        );
+        assert_eq!(
+            subscription_messages(&[Subscription::TrackAddress(String::from("bc1qa"))]),
+            vec![r#"{"track-address":"bc1qa"}"#]
+        );
+    }
+
+    #[test]
+    fn parse_events_keys() {
+        // Expectency: Each key of a message is parsed into its own event, unknown keys are
+        //             passed through and malformed values are reported as error.
+        let events = parse_events(
+            r#"{
+                "conversions": {"time": 1, "USD": 60000},
+                "txConfirmed": "a1",
+                "vBytesPerSecond": 1523,
+                "loadingIndicators": {},
+                "fees": {"fastestFee": "fast"}
+            }"#,
+        );
+        assert_eq!(events.len(), 5);
+        let contains = |predicate: &dyn Fn(&EventResult) -> bool| events.iter().any(predicate);
+        assert!(contains(&|event| matches!(
+            event,
+            Ok(MempoolEvent::Conversions(prices)) if prices.get(Currency::Usd) == Some(60000.0)
+        )));
+        assert!(contains(&|event| matches!(
+            event,
+            Ok(MempoolEvent::TxConfirmed(Some(txid))) if txid == "a1"
+        )));
+        assert!(contains(&|event| matches!(
+            event,
+            Ok(MempoolEvent::VBytesPerSecond(rate)) if *rate == 1523.0
+        )));
+        assert!(contains(&|event| matches!(
+            event,
+            Ok(MempoolEvent::Other(key, _)) if key == "loadingIndicators"
+        )));
+        assert!(contains(&|event| matches!(
+            event,
+            Err(MempoolError::Decode(_, _))
+        )));
+        assert!(matches!(
+            parse_events("[]")[0],
+            Err(MempoolError::Decode(_, _))
+        ));
+    }
+
+    #[tokio::test]
+    async fn mempool_stream_reconnect() {
+        // Expectency: After the server closed the connection the stream must reconnect,
+        //             announce the reconnect and subscribe again.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/api/v1/ws", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let mut received = Vec::new();
+            for reply in [r#"{"vBytesPerSecond": 1523}"#, r#"{"mempool-blocks": []}"#] {
+                let (tcp, _) = listener.accept().await.unwrap();
+                let mut socket = accept_async(tcp).await.unwrap();
+                received.push(socket.next().await.unwrap().unwrap().into_text().unwrap());
+                socket
+                    .send(Message::Text(String::from(reply)))
+                    .await
+                    .unwrap();
+                socket.close(None).await.unwrap();
+            }
+            received
+        });
+
+        let mut stream = MempoolStream::new(&url)
+            .subscribe(Subscription::Stats)
+            .set_reconnect_delay(Duration::from_millis(10));
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            MempoolEvent::VBytesPerSecond(1523.0)
+        );
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            MempoolEvent::Reconnected
+        );
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            MempoolEvent::MempoolBlocks(Vec::new())
+        );
+
+        let received = server.await.unwrap();
+        let want = r#"{"action":"want","data":["stats"]}"#;
+        assert_eq!(received, vec![want, want]);
+    }
+
+    #[tokio::test]
+    async fn mempool_stream_connection_refused() {
+        // Expectency: Failed connection attempts are yielded as errors without ending the stream.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let mut stream = MempoolStream::new(&url).set_reconnect_delay(Duration::from_millis(10));
+        for _ in 0..2 {
+            assert!(matches!(
+                stream.next().await.unwrap(),
+                Err(MempoolError::Transport(_, _))
+            ));
+        }
+    }
+}
@@ -7,14 +7,21 @@
 //! Module containing "Target" related functionality.
 
 // Imports
-use super::{CheckTargetError, ParseTargetError, ResolvePolicy};
+use super::transport::{default_transport, HttpMethod, HttpRequest, HttpResponse, HttpTransport};
+use super::{CheckTargetError, MempoolClient, Network, ParseTargetError, ResolvePolicy};
+use regex::Regex;
+use serde_json::Value;
 use std::convert::From;
+use std::error::Error;
 use std::fmt::{self};
+use std::io;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6, TcpStream};
 use std::num::ParseIntError;
+use std::ops::RangeInclusive;
 use std::process::{Command, Stdio};
 use std::str::FromStr;
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
 // Test imports
 #[cfg(test)]
@@ -23,6 +30,12 @@ use mockall::automock;
 /// Default timeout duration for each connection attempt of a [TcpTarget]
 pub const DEFAULT_TCP_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
 
+/// Default timeout duration for each request of a [HttpTarget]
+pub const DEFAULT_HTTP_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Default maximum age of the newest block of a [MempoolApiTarget]
+pub const DEFAULT_MAX_TIP_AGE: Duration = Duration::from_secs(2 * 60 * 60);
+
 /// Alias of String expressing a "fully qualified domain name"
 pub type Fqhn = String;
 
@@ -40,7 +53,7 @@ pub trait Target {
     /// # Example
     /// ```
     /// # use std::str::FromStr;
-    /// # use reachable::{Target, IcmpTarget};
+    /// # use mempool_space::{Target, IcmpTarget};
     ///
     /// assert_eq!(IcmpTarget::from_str("127.0.0.1").unwrap().get_id(), "127.0.0.1");
     /// ```
@@ -59,7 +72,7 @@ pub trait Target {
     /// # Example
     /// ```
     /// # use std::str::FromStr;
-    /// # use reachable::{Status, Target, IcmpTarget};
+    /// # use mempool_space::{Status, Target, IcmpTarget};
     ///
     /// assert_eq!(
     ///     IcmpTarget::from_str("127.0.0.1").unwrap().check_availability().unwrap(),
@@ -407,6 +420,425 @@ impl FromStr for TcpTarget {
     }
 }
 
+/// Target to check if an HTTP(S) endpoint answers as expected.
+///
+/// # Notes
+/// HttpTargets are available if the response status is within one of the available status
+/// ranges (default 200-299) and the body satisfies all configured assertions. Failed requests
+/// are treated as a sign of the target being not available.
+///
+/// Body assertions are evaluated against the response body, so they always fail for HEAD requests.
+///
+/// # Example
+/// ```no_run
+/// # use std::str::FromStr;
+/// # use mempool_space::{HttpTarget, Status, Target};
+///
+/// let target = HttpTarget::from_str("https://mempool.space/api/blocks/tip/height")
+///     .unwrap()
+///     .set_body_regex(r"^\d+$")
+///     .unwrap();
+/// assert_eq!(target.check_availability().unwrap(), Status::Available);
+/// ```
+#[derive(Debug)]
+pub struct HttpTarget {
+    /// Url to request.
+    url: String,
+    /// [HttpMethod] to request the url with.
+    method: HttpMethod,
+    /// Ranges of status codes treated as available.
+    available_status: Vec<RangeInclusive<u16>>,
+    /// [Regex] the response body must match, if any.
+    body_regex: Option<Regex>,
+    /// JSON pointer, e.g. "/0/height", that must resolve to a non-null value, if any.
+    json_pointer: Option<String>,
+    /// [Duration] used as request timeout.
+    timeout: Duration,
+    /// [HttpTransport] performing the requests.
+    transport: Arc<dyn HttpTransport>,
+}
+
+impl HttpTarget {
+    /// Construct an [HttpTarget] sending GET requests without body assertions.
+    ///
+    /// # Arguments
+    /// * url: absolute url to request, e.g. "https://mempool.space/api/blocks/tip/height".
+    ///
+    /// # Returns
+    /// Instance of [HttpTarget] treating status 200-299 as available and using
+    /// [DEFAULT_HTTP_REQUEST_TIMEOUT].
+    ///
+    /// # Notes
+    /// The url is not validated, for more convenience use the implementation of trait "FromStr".
+    pub fn new(url: &str) -> Self {
+        HttpTarget {
+            url: String::from(url),
+            method: HttpMethod::Get,
+            available_status: vec![200..=299],
+            body_regex: None,
+            json_pointer: None,
+            timeout: DEFAULT_HTTP_REQUEST_TIMEOUT,
+            transport: default_transport(),
+        }
+    }
+
+    /// Set a new [HttpMethod] to request the url with, e.g. [HttpMethod::Head].
+    pub fn set_method(mut self, method: HttpMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Set new ranges of status codes treated as available, e.g. `vec![200..=299, 304..=304]`.
+    pub fn set_available_status(mut self, available_status: Vec<RangeInclusive<u16>>) -> Self {
+        self.available_status = available_status;
+        self
+    }
+
+    /// Set a regular expression the response body must match.
+    ///
+    /// # Returns
+    /// * On success, the [HttpTarget] using the regular expression.
+    /// * On failure, a [ParseTargetError] if the pattern is no valid regular expression.
+    pub fn set_body_regex(mut self, pattern: &str) -> Result<Self, ParseTargetError> {
+        let regex = Regex::new(pattern).map_err(|error| {
+            ParseTargetError::from((
+                "Failed to parse body regex",
+                Box::new(error) as Box<dyn Error>,
+            ))
+        })?;
+        self.body_regex = Some(regex);
+        Ok(self)
+    }
+
+    /// Set a JSON pointer (RFC 6901), e.g. "/0/height", that must resolve to a non-null value
+    /// within the JSON response body.
+    pub fn set_json_pointer(mut self, json_pointer: &str) -> Self {
+        self.json_pointer = Some(String::from(json_pointer));
+        self
+    }
+
+    /// Set a new timeout [Duration] for each request.
+    pub fn set_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set a new [HttpTransport] performing the requests.
+    pub fn set_transport(mut self, transport: Arc<dyn HttpTransport>) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Get a reference to the url to request.
+    pub fn get_url(&self) -> &str {
+        &self.url
+    }
+
+    /// Get the [HttpMethod] in use.
+    pub fn get_method(&self) -> HttpMethod {
+        self.method
+    }
+
+    /// Get a reference to the ranges of status codes treated as available.
+    pub fn get_available_status(&self) -> &[RangeInclusive<u16>] {
+        &self.available_status
+    }
+
+    /// Get a reference to the [Regex] the response body must match, if any.
+    pub fn get_body_regex(&self) -> Option<&Regex> {
+        self.body_regex.as_ref()
+    }
+
+    /// Get a reference to the JSON pointer that must resolve, if any.
+    pub fn get_json_pointer(&self) -> Option<&str> {
+        self.json_pointer.as_deref()
+    }
+
+    /// Get a reference to the timeout [Duration] in use.
+    pub fn get_timeout(&self) -> &Duration {
+        &self.timeout
+    }
+
+    /// Check if given response satisfies the status range and all body assertions.
+    fn is_expected(&self, response: &HttpResponse) -> bool {
+        let status = response.get_status();
+        if !self
+            .available_status
+            .iter()
+            .any(|range| range.contains(&status))
+        {
+            return false;
+        }
+
+        let body = String::from_utf8_lossy(response.get_body());
+        if let Some(regex) = &self.body_regex {
+            if !regex.is_match(&body) {
+                return false;
+            }
+        }
+        if let Some(json_pointer) = &self.json_pointer {
+            let json: Value = match serde_json::from_str(&body) {
+                Ok(json) => json,
+                Err(_) => return false,
+            };
+            match json.pointer(json_pointer) {
+                Some(value) if !value.is_null() => (),
+                _ => return false,
+            }
+        }
+        true
+    }
+}
+
+impl Target for HttpTarget {
+    fn get_id(&self) -> String {
+        String::from(self.get_url())
+    }
+
+    fn check_availability(&self) -> Result<Status, CheckTargetError> {
+        // Note: Failed requests, e.g. refused connections or timeouts, are treated as a
+        // sign of the target being not available.
+        let request = HttpRequest::new(self.method, &self.url).set_timeout(self.timeout);
+        match self.transport.send(&request) {
+            Ok(response) if self.is_expected(&response) => Ok(Status::Available),
+            _ => Ok(Status::NotAvailable),
+        }
+    }
+}
+
+impl FromStr for HttpTarget {
+    type Err = ParseTargetError;
+
+    fn from_str(s: &str) -> Result<HttpTarget, Self::Err> {
+        let scheme = s.split_once("://").map(|(scheme, _)| scheme.to_lowercase());
+        if !matches!(scheme.as_deref(), Some("http") | Some("https")) {
+            return Err(ParseTargetError::from(
+                "Missing 'http://' or 'https://' scheme",
+            ));
+        }
+        tcp_target_from_url(s)?;
+        Ok(HttpTarget::new(s))
+    }
+}
+
+/// Target to check if a mempool backend is available and in sync with the chain.
+///
+/// # Notes
+/// MempoolApiTargets request the newest block of the backend. They are not available if
+/// the request fails, the newest block is older than the maximum tip age or, if a reference
+/// backend is set, the tip height lags the tip height of the reference by more than the
+/// maximum tip lag.
+///
+/// Block timestamps may be up to two hours off and blocks are found in irregular intervals,
+/// so the maximum tip age should not be chosen too small.
+///
+/// # Example
+/// ```no_run
+/// # use std::time::Duration;
+/// # use mempool_space::{MempoolApiTarget, MempoolClient, Network, Status, Target};
+///
+/// let target = MempoolApiTarget::new(MempoolClient::new("http://mempool.local:8999/api"))
+///     .set_max_tip_age(Duration::from_secs(3 * 60 * 60))
+///     .set_reference(MempoolClient::from(Network::Mainnet), 1);
+/// assert_eq!(target.check_availability().unwrap(), Status::Available);
+/// ```
+#[derive(Debug)]
+pub struct MempoolApiTarget {
+    /// [MempoolClient] querying the backend to check.
+    client: MempoolClient,
+    /// Maximum age of the newest block.
+    max_tip_age: Duration,
+    /// [MempoolClient] querying the reference backend, if any.
+    reference: Option<MempoolClient>,
+    /// Maximum number of blocks the tip height may lag the reference.
+    max_tip_lag: u64,
+}
+
+impl MempoolApiTarget {
+    /// Construct a [MempoolApiTarget] without reference backend.
+    ///
+    /// # Arguments
+    /// * client: [MempoolClient] querying the backend to check.
+    ///
+    /// # Returns
+    /// Instance of [MempoolApiTarget] using [DEFAULT_MAX_TIP_AGE].
+    ///
+    /// # Notes
+    /// For more convenience use the implementations of trait "From" and "FromStr".
+    pub fn new(client: MempoolClient) -> Self {
+        MempoolApiTarget {
+            client,
+            max_tip_age: DEFAULT_MAX_TIP_AGE,
+            reference: None,
+            max_tip_lag: 0,
+        }
+    }
+
+    /// Set a new maximum age of the newest block.
+    pub fn set_max_tip_age(mut self, max_tip_age: Duration) -> Self {
+        self.max_tip_age = max_tip_age;
+        self
+    }
+
+    /// Set a reference backend the tip height is compared with.
+    ///
+    /// # Arguments
+    /// * reference: [MempoolClient] querying the reference backend.
+    /// * max_tip_lag: maximum number of blocks the tip height may lag the reference.
+    pub fn set_reference(mut self, reference: MempoolClient, max_tip_lag: u64) -> Self {
+        self.reference = Some(reference);
+        self.max_tip_lag = max_tip_lag;
+        self
+    }
+
+    /// Get a reference to the [MempoolClient] querying the backend to check.
+    pub fn get_client(&self) -> &MempoolClient {
+        &self.client
+    }
+
+    /// Get a reference to the maximum age of the newest block.
+    pub fn get_max_tip_age(&self) -> &Duration {
+        &self.max_tip_age
+    }
+
+    /// Get a reference to the [MempoolClient] querying the reference backend, if any.
+    pub fn get_reference(&self) -> Option<&MempoolClient> {
+        self.reference.as_ref()
+    }
+
+    /// Get the maximum number of blocks the tip height may lag the reference.
+    pub fn get_max_tip_lag(&self) -> u64 {
+        self.max_tip_lag
+    }
+}
+
+impl Target for MempoolApiTarget {
+    fn get_id(&self) -> String {
+        String::from(self.client.get_base_url())
+    }
+
+    fn check_availability(&self) -> Result<Status, CheckTargetError> {
+        // Note: Failed requests on the backend to check are treated as a sign of the target
+        // being not available. Failed requests on the reference are internal errors, since
+        // the availability can't be determined.
+        let tip = match self.client.get_blocks(None) {
+            Ok(blocks) => match blocks.into_iter().next() {
+                Some(tip) => tip,
+                None => return Ok(Status::NotAvailable),
+            },
+            Err(_) => return Ok(Status::NotAvailable),
+        };
+
+        let tip_time = SystemTime::UNIX_EPOCH + Duration::from_secs(tip.timestamp);
+        let tip_age = SystemTime::now()
+            .duration_since(tip_time)
+            .unwrap_or_default();
+        if tip_age > self.max_tip_age {
+            return Ok(Status::NotAvailable);
+        }
+
+        if let Some(reference) = &self.reference {
+            let reference_height = reference.get_block_tip_height().map_err(|error| {
+                CheckTargetError::from((
+                    "Failed to query reference tip height",
+                    Box::new(error) as Box<dyn Error>,
+                ))
+            })?;
+            if reference_height.saturating_sub(tip.height) > self.max_tip_lag {
+                return Ok(Status::NotAvailable);
+            }
+        }
+        Ok(Status::Available)
+    }
+}
+
+impl From<Network> for MempoolApiTarget {
+    fn from(network: Network) -> Self {
+        MempoolApiTarget::new(MempoolClient::from(network))
+    }
+}
+
+impl FromStr for MempoolApiTarget {
+    type Err = ParseTargetError;
+
+    /// Parse a [MempoolApiTarget] from a [Network] name or base url, e.g. "signet" or
+    /// "http://192.168.1.10:8999/api".
+    fn from_str(s: &str) -> Result<MempoolApiTarget, Self::Err> {
+        let network = Network::from_str(s).map_err(|error| {
+            ParseTargetError::from(("Failed to parse network", Box::new(error) as Box<dyn Error>))
+        })?;
+        Ok(MempoolApiTarget::from(network))
+    }
+}
+
+/// Construct the [TcpTarget] checking the availability of the host of given url.
+///
+/// # Notes
+/// Supports the schemes http, https, ws and wss, the port defaults to the schemes port.
+pub(crate) fn tcp_target_from_url(base_url: &str) -> Result<TcpTarget, ParseTargetError> {
+    let (scheme, rest) = base_url
+        .split_once("://")
+        .ok_or(ParseTargetError::from("Missing '://' after scheme"))?;
+    let default_port = match scheme.to_lowercase().as_str() {
+        "http" | "ws" => "80",
+        "https" | "wss" => "443",
+        _ => return Err(ParseTargetError::from("Unsupported scheme")),
+    };
+
+    // Extract authority and strip user information
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let authority = authority.rsplit('@').next().unwrap_or_default();
+
+    // Split host and optional port, IPv6 addresses are enclosed in brackets
+    let (fqhn, port) = match authority.strip_prefix('[') {
+        Some(bracketed) => {
+            let (fqhn, rest) = bracketed
+                .split_once(']')
+                .ok_or(ParseTargetError::from("Missing ']' after IPv6 address"))?;
+            (fqhn, rest.strip_prefix(':'))
+        }
+        None => match authority.split_once(':') {
+            Some((fqhn, port)) => (fqhn, Some(port)),
+            None => (authority, None),
+        },
+    };
+    if fqhn.is_empty() {
+        return Err(ParseTargetError::from("No FQHN found"));
+    }
+    TcpTarget::from_str(&format!("{}:{}", fqhn, port.unwrap_or(default_port)))
+}
+
+/// Probe each address of given FQHN and port until a probe succeeds.
+///
+/// # Arguments
+/// * resolve_policy: [ResolvePolicy] used to resolve the FQHN.
+/// * fqhn: the FQHN to resolve.
+/// * port: the port probed on each address.
+/// * probe: the protocol specific probe, e.g. a handshake.
+///
+/// # Returns
+/// * On success, the result of the first successful probe, None if all probes failed.
+/// * On failure, a [CheckTargetError] if the FQHN could not be resolved.
+pub(crate) fn probe_addrs<T>(
+    resolve_policy: &ResolvePolicy,
+    fqhn: &str,
+    port: Port,
+    probe: impl Fn(SocketAddr) -> io::Result<T>,
+) -> Result<Option<T>, CheckTargetError> {
+    // Try for each address/port pair to run the probe. Occurring errors, e.g. peers of
+    // other networks or insane chain tips, are treated as a sign of target is not available.
+    let addrs = resolve_policy.resolve(fqhn)?;
+    Ok(addrs
+        .into_iter()
+        .map(|addr| SocketAddr::from((addr, port)))
+        .find_map(|addr| probe(addr).ok()))
+}
+
+/// Construct an [io::Error] of kind InvalidData.
+pub(crate) fn invalid_data(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
 #[cfg(test)]
 mod tests {
     use std::net::TcpListener;
@@ -414,6 +846,8 @@ mod tests {
     use std::time::Duration;
 
     use super::*;
+    use crate::test_utils::{closed_addr, response, serve};
+    use crate::transport::MockHttpTransport;
 
     // IcmpTarget tests
     #[test]
@@ -707,4 +1141,194 @@ mod tests {
             "ResolveTargetError caused by: Given Policy filtered all resolved addresses"
         );
     }
+
+    // HttpTarget tests
+    fn mock_transport(status: u16, body: &'static str) -> Arc<MockHttpTransport> {
+        let mut transport = MockHttpTransport::new();
+        transport
+            .expect_send()
+            .returning(move |_| Ok(HttpResponse::new(status, body.as_bytes().to_vec())));
+        Arc::new(transport)
+    }
+
+    #[test]
+    fn http_target_from_str_valid() {
+        // Expectency: HttpTargets are parsed from http and https urls with GET defaults.
+        let target = HttpTarget::from_str("https://mempool.space/api/blocks/tip/height").unwrap();
+        assert_eq!(
+            target.get_url(),
+            "https://mempool.space/api/blocks/tip/height"
+        );
+        assert_eq!(target.get_method(), HttpMethod::Get);
+        assert_eq!(target.get_available_status(), &[200..=299]);
+        assert!(target.get_body_regex().is_none());
+        assert_eq!(target.get_json_pointer(), None);
+        assert_eq!(target.get_timeout(), &DEFAULT_HTTP_REQUEST_TIMEOUT);
+        assert_eq!(
+            target.get_id(),
+            "https://mempool.space/api/blocks/tip/height"
+        );
+    }
+
+    #[test]
+    fn http_target_from_str_invalid() {
+        // Expectency: Urls without http(s) scheme, host or with invalid port are rejected.
+        assert!(HttpTarget::from_str("mempool.space/api").is_err());
+        assert!(HttpTarget::from_str("wss://mempool.space/api/v1/ws").is_err());
+        assert!(HttpTarget::from_str("https:///api").is_err());
+        assert!(HttpTarget::from_str("https://mempool.space:0/api").is_err());
+        assert!(HttpTarget::new("https://mempool.space")
+            .set_body_regex("(")
+            .is_err());
+    }
+
+    #[test]
+    fn http_target_check_availability() {
+        // Expectency: A GET request is sent and a success status means available.
+        let (base_url, srv) = serve(vec![response(200, "840000")]);
+        let target = HttpTarget::from_str(&format!("{}/api/blocks/tip/height", base_url)).unwrap();
+        assert_eq!(target.check_availability().unwrap(), Status::Available);
+        let requests = srv.join().unwrap();
+        assert!(requests[0].starts_with("GET /api/blocks/tip/height HTTP/1.1"));
+    }
+
+    #[test]
+    fn http_target_check_unavailability() {
+        // Expectency: Unexpected status codes and failed requests mean not available.
+        let target = HttpTarget::new("http://mock").set_transport(mock_transport(503, ""));
+        assert_eq!(target.check_availability().unwrap(), Status::NotAvailable);
+
+        let target = HttpTarget::new(&format!("http://{}", closed_addr()))
+            .set_timeout(Duration::from_secs(1));
+        assert_eq!(target.check_availability().unwrap(), Status::NotAvailable);
+    }
+
+    #[test]
+    fn http_target_check_availability_status_ranges() {
+        // Expectency: Configured status ranges replace the default success range.
+        let target = HttpTarget::new("http://mock")
+            .set_method(HttpMethod::Head)
+            .set_available_status(vec![200..=299, 404..=404])
+            .set_transport(mock_transport(404, ""));
+        assert_eq!(target.check_availability().unwrap(), Status::Available);
+    }
+
+    #[test]
+    fn http_target_check_availability_body_regex() {
+        // Expectency: The body must match the configured regular expression.
+        let target = HttpTarget::new("http://mock")
+            .set_body_regex(r"^\d+$")
+            .unwrap()
+            .set_transport(mock_transport(200, "840000"));
+        assert_eq!(target.check_availability().unwrap(), Status::Available);
+
+        let target = HttpTarget::new("http://mock")
+            .set_body_regex(r"^\d+$")
+            .unwrap()
+            .set_transport(mock_transport(200, "<html>maintenance</html>"));
+        assert_eq!(target.check_availability().unwrap(), Status::NotAvailable);
+    }
+
+    #[test]
+    fn http_target_check_availability_json_pointer() {
+        // Expectency: The JSON pointer must resolve to a non-null value within a JSON body.
+        let body = r#"[{"height":840000,"extras":null}]"#;
+        let target = HttpTarget::new("http://mock")
+            .set_json_pointer("/0/height")
+            .set_transport(mock_transport(200, body));
+        assert_eq!(target.check_availability().unwrap(), Status::Available);
+
+        for json_pointer in ["/0/extras", "/1/height"] {
+            let target = HttpTarget::new("http://mock")
+                .set_json_pointer(json_pointer)
+                .set_transport(mock_transport(200, body));
+            assert_eq!(target.check_availability().unwrap(), Status::NotAvailable);
+        }
+
+        let target = HttpTarget::new("http://mock")
+            .set_json_pointer("/0/height")
+            .set_transport(mock_transport(200, "840000 blocks"));
+        assert_eq!(target.check_availability().unwrap(), Status::NotAvailable);
+    }
+
+    // MempoolApiTarget tests
+    fn blocks_page(height: u64, age: Duration) -> String {
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .saturating_sub(age)
+            .as_secs();
+        format!(
+            r#"[{{"id":"00","height":{},"version":2,"timestamp":{},"tx_count":1,"size":1,"weight":4,"merkle_root":"00","previousblockhash":null,"mediantime":{},"nonce":0,"bits":0,"difficulty":1.0}}]"#,
+            height, timestamp, timestamp
+        )
+    }
+
+    #[test]
+    fn mempool_api_target_from_str() {
+        // Expectency: MempoolApiTargets are parsed from network names and base urls.
+        let target = MempoolApiTarget::from_str("signet").unwrap();
+        assert_eq!(target.get_id(), Network::Signet.base_url());
+        assert_eq!(target.get_max_tip_age(), &DEFAULT_MAX_TIP_AGE);
+        assert!(target.get_reference().is_none());
+
+        let target = MempoolApiTarget::from_str("http://10.0.0.2:8999/api").unwrap();
+        assert_eq!(
+            target.get_client().get_base_url(),
+            "http://10.0.0.2:8999/api"
+        );
+        assert!(MempoolApiTarget::from_str("").is_err());
+    }
+
+    #[test]
+    fn mempool_api_target_check_availability() {
+        // Expectency: A recent tip in sync with the reference means available.
+        let (base_url, srv) = serve(vec![response(
+            200,
+            &blocks_page(840_000, Duration::from_secs(600)),
+        )]);
+        let (reference_url, reference_srv) = serve(vec![response(200, "840001")]);
+        let target = MempoolApiTarget::new(MempoolClient::new(&base_url))
+            .set_reference(MempoolClient::new(&reference_url), 1);
+        assert_eq!(target.get_max_tip_lag(), 1);
+        assert_eq!(target.check_availability().unwrap(), Status::Available);
+        assert!(srv.join().unwrap()[0].starts_with("GET /blocks HTTP/1.1"));
+        assert!(reference_srv.join().unwrap()[0].starts_with("GET /blocks/tip/height HTTP/1.1"));
+    }
+
+    #[test]
+    fn mempool_api_target_check_unavailability() {
+        // Expectency: Stale tips, lagging tips and failed requests mean not available.
+        let (base_url, srv) = serve(vec![
+            response(200, &blocks_page(840_000, Duration::from_secs(3 * 60 * 60))),
+            response(200, &blocks_page(840_000, Duration::from_secs(600))),
+            response(502, ""),
+            response(200, "[]"),
+        ]);
+        let (reference_url, reference_srv) = serve(vec![response(200, "840002")]);
+        let target = MempoolApiTarget::new(MempoolClient::new(&base_url))
+            .set_reference(MempoolClient::new(&reference_url), 1);
+        assert_eq!(target.check_availability().unwrap(), Status::NotAvailable);
+        assert_eq!(target.check_availability().unwrap(), Status::NotAvailable);
+        assert_eq!(target.check_availability().unwrap(), Status::NotAvailable);
+        assert_eq!(target.check_availability().unwrap(), Status::NotAvailable);
+        srv.join().unwrap();
+        reference_srv.join().unwrap();
+    }
+
+    #[test]
+    fn mempool_api_target_check_availability_reference_error() {
+        // Expectency: A failing reference can't be compared with and is reported as error.
+        let (base_url, srv) = serve(vec![response(
+            200,
+            &blocks_page(840_000, Duration::from_secs(600)),
+        )]);
+        let reference = MempoolClient::new(&format!("http://{}", closed_addr()));
+        let target = MempoolApiTarget::new(MempoolClient::new(&base_url))
+            .set_max_tip_age(Duration::from_secs(900))
+            .set_reference(reference, 0);
+        assert!(format!("{}", target.check_availability().unwrap_err())
+            .starts_with("Failed to query reference tip height"));
+        srv.join().unwrap();
+    }
 }
@@ -0,0 +1,106 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Module containing helpers shared between unit tests.
+
+// Imports
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener};
+use std::thread::{spawn, JoinHandle};
+
+/// Get a local address nothing is listening on, by binding an ephemeral port and releasing it.
+pub fn closed_addr() -> SocketAddr {
+    TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+}
+
+/// Spawn a local HTTP server answering each of the given responses on a separate connection.
+///
+/// # Arguments
+/// * responses: complete HTTP responses (status line, headers and body) to send in order.
+///
+/// # Returns
+/// The base url of the server and a handle returning all received request heads once
+/// every response was sent.
+pub fn serve(responses: Vec<String>) -> (String, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+    let handle = spawn(move || {
+        let mut requests = Vec::new();
+        for response in responses {
+            // Note: Connections closed without a request stem from availability checks
+            let (mut stream, request) = loop {
+                let (mut stream, _) = listener.accept().unwrap();
+                let request = read_request(&mut stream);
+                if !request.is_empty() {
+                    break (stream, request);
+                }
+            };
+            requests.push(request);
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+        requests
+    });
+    (base_url, handle)
+}
+
+/// Construct a HTTP response with the given status code and body.
+pub fn response(status: u16, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {} Status\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+/// Construct a JSON page of minimal transactions with the given txids.
+pub fn transaction_page(txids: &[&str]) -> String {
+    let txs: Vec<String> = txids
+        .iter()
+        .map(|txid| {
+            format!(
+                r#"{{"txid":"{}","version":2,"locktime":0,"vin":[],"vout":[],"size":0,"weight":0,"fee":0,"status":{{"confirmed":true}}}}"#,
+                txid
+            )
+        })
+        .collect();
+    format!("[{}]", txs.join(","))
+}
+
+/// Read a HTTP request head and body (if announced via Content-Length) from given stream.
+fn read_request(stream: &mut impl Read) -> String {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        match stream.read(&mut buf).unwrap() {
+            0 => break,
+            n => request.extend_from_slice(&buf[..n]),
+        }
+    }
+
+    let request = String::from_utf8_lossy(&request).into_owned();
+    let head_len = request
+        .find("\r\n\r\n")
+        .map(|index| index + 4)
+        .unwrap_or(request.len());
+    let content_length = request
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+
+    let mut body = request.as_bytes()[head_len..].to_vec();
+    while body.len() < content_length {
+        match stream.read(&mut buf).unwrap() {
+            0 => break,
+            n => body.extend_from_slice(&buf[..n]),
+        }
+    }
+    format!("{}{}", &request[..head_len], String::from_utf8_lossy(&body))
+}
@@ -0,0 +1,196 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Module containing types returned by the mempool.space transaction endpoints.
+
+// Imports
+use serde::Deserialize;
+
+/// A transaction as returned by `GET /tx/:txid`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Transaction {
+    /// Transaction id
+    pub txid: String,
+    /// Transaction version
+    pub version: u32,
+    /// Transaction locktime
+    pub locktime: u32,
+    /// Inputs spent by this transaction
+    pub vin: Vec<Vin>,
+    /// Outputs created by this transaction
+    pub vout: Vec<Vout>,
+    /// Serialized size in bytes
+    pub size: u64,
+    /// Transaction weight in weight units
+    pub weight: u64,
+    /// Fee paid in satoshis
+    pub fee: u64,
+    /// Confirmation status
+    pub status: TxStatus,
+}
+
+/// Confirmation status of a [Transaction].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TxStatus {
+    /// True if the transaction is included in a block
+    pub confirmed: bool,
+    /// Height of the confirming block
+    pub block_height: Option<u64>,
+    /// Hash of the confirming block
+    pub block_hash: Option<String>,
+    /// Timestamp of the confirming block
+    pub block_time: Option<u64>,
+}
+
+/// An input of a [Transaction].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Vin {
+    /// Transaction id of the spent output
+    pub txid: String,
+    /// Index of the spent output
+    pub vout: u32,
+    /// The spent output. Missing for coinbase inputs.
+    pub prevout: Option<Vout>,
+    /// Hex encoded scriptSig
+    pub scriptsig: String,
+    /// Disassembled scriptSig
+    pub scriptsig_asm: String,
+    /// Hex encoded witness stack items
+    #[serde(default)]
+    pub witness: Vec<String>,
+    /// True if this input is a coinbase input
+    pub is_coinbase: bool,
+    /// Input sequence number
+    pub sequence: u32,
+}
+
+/// An output of a [Transaction].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Vout {
+    /// Hex encoded scriptPubKey
+    pub scriptpubkey: String,
+    /// Disassembled scriptPubKey
+    pub scriptpubkey_asm: String,
+    /// Type of the scriptPubKey e.g. "v0_p2wpkh"
+    pub scriptpubkey_type: String,
+    /// Address of the scriptPubKey, if any
+    pub scriptpubkey_address: Option<String>,
+    /// Output value in satoshis
+    pub value: u64,
+}
+
+/// Spending status of a [Vout] as returned by `GET /tx/:txid/outspend/:vout`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Outspend {
+    /// True if the output is spent by a confirmed or unconfirmed transaction
+    pub spent: bool,
+    /// Transaction id of the spending transaction
+    pub txid: Option<String>,
+    /// Index of the spending input
+    pub vin: Option<u32>,
+    /// Confirmation status of the spending transaction
+    pub status: Option<TxStatus>,
+}
+
+/// Merkle inclusion proof of a [Transaction] as returned by `GET /tx/:txid/merkle-proof`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MerkleProof {
+    /// Height of the block including the transaction
+    pub block_height: u64,
+    /// Hex encoded sibling hashes from the transaction up to the merkle root
+    pub merkle: Vec<String>,
+    /// Position of the transaction within the block
+    pub pos: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transaction_deserialize() {
+        // Expectency: A transaction response of the esplora API must be parsed into a Transaction.
+        let json = r#"{
+            "txid": "15e10745f15593a899cef391191bdd3d7c12412cc4696b7bcb669d0feadc8521",
+            "version": 1,
+            "locktime": 0,
+            "vin": [{
+                "txid": "0000000000000000000000000000000000000000000000000000000000000000",
+                "vout": 4294967295,
+                "prevout": null,
+                "scriptsig": "03",
+                "scriptsig_asm": "OP_PUSHBYTES_3",
+                "witness": ["00"],
+                "is_coinbase": true,
+                "sequence": 4294967295
+            }],
+            "vout": [{
+                "scriptpubkey": "0014",
+                "scriptpubkey_asm": "OP_0 OP_PUSHBYTES_20",
+                "scriptpubkey_type": "v0_p2wpkh",
+                "scriptpubkey_address": "bc1qxyz",
+                "value": 625000000
+            }],
+            "size": 180,
+            "weight": 612,
+            "fee": 0,
+            "status": {
+                "confirmed": true,
+                "block_height": 840000,
+                "block_hash": "0000000000000000000320283a032748cef8227873ff4872689bf23f1cda83a5",
+                "block_time": 1713571767
+            }
+        }"#;
+        let tx: Transaction = serde_json::from_str(json).unwrap();
+        assert_eq!(tx.vin.len(), 1);
+        assert!(tx.vin[0].is_coinbase);
+        assert_eq!(tx.vin[0].prevout, None);
+        assert_eq!(tx.vout[0].value, 625000000);
+        assert_eq!(tx.status.block_height, Some(840000));
+    }
+
+    #[test]
+    fn tx_status_deserialize_unconfirmed() {
+        // Expectency: The block related fields of an unconfirmed TxStatus are missing.
+        let status: TxStatus = serde_json::from_str(r#"{"confirmed": false}"#).unwrap();
+        assert!(!status.confirmed);
+        assert_eq!(status.block_height, None);
+        assert_eq!(status.block_hash, None);
+        assert_eq!(status.block_time, None);
+    }
+
+    #[test]
+    fn outspend_deserialize() {
+        // Expectency: Unspent outputs only report the spent flag, spent outputs the spending input.
+        let unspent: Outspend = serde_json::from_str(r#"{"spent": false}"#).unwrap();
+        assert!(!unspent.spent);
+        assert_eq!(unspent.txid, None);
+        assert_eq!(unspent.status, None);
+
+        let json = r#"{
+            "spent": true,
+            "txid": "2a5b1a1a3f7e0b0e5a1d5d4ee1a0c3fa6c6c7e1c1e2b1c0c6f2f8f5e8d1f2a3b",
+            "vin": 1,
+            "status": {"confirmed": false}
+        }"#;
+        let spent: Outspend = serde_json::from_str(json).unwrap();
+        assert!(spent.spent);
+        assert_eq!(spent.vin, Some(1));
+        assert!(!spent.status.unwrap().confirmed);
+    }
+
+    #[test]
+    fn merkle_proof_deserialize() {
+        // Expectency: A merkle proof must be parsed into a MerkleProof.
+        let json = r#"{
+            "block_height": 363348,
+            "merkle": ["acf931fe8980c6165b32fe7a8d25f779af7870a638599db1977d5309e24d2478"],
+            "pos": 1465
+        }"#;
+        let proof: MerkleProof = serde_json::from_str(json).unwrap();
+        assert_eq!(proof.block_height, 363348);
+        assert_eq!(proof.merkle.len(), 1);
+        assert_eq!(proof.pos, 1465);
+    }
+}
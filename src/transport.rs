@@ -0,0 +1,568 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Module containing the pluggable HTTP transports of the [MempoolClient] and the
+//! [AsyncMempoolClient].
+//!
+//! # Notes
+//! Implementations are selected via the features "transport-reqwest" (default) and
+//! "transport-ureq". If both are enabled, reqwest is used by default. Without reqwest, the
+//! [AsyncMempoolClient] offloads the blocking transport onto separate threads.
+
+// Imports
+use super::error::MempoolError;
+#[cfg(feature = "async")]
+use futures::future::{BoxFuture, FutureExt};
+use std::fmt::{self, Debug};
+#[cfg(any(
+    feature = "transport-reqwest",
+    feature = "transport-ureq",
+    feature = "async"
+))]
+use std::sync::Arc;
+use std::time::Duration;
+
+// Test imports
+#[cfg(test)]
+use mockall::automock;
+
+// Documentation imports
+#[cfg(all(doc, feature = "async"))]
+use super::AsyncMempoolClient;
+#[cfg(doc)]
+use super::MempoolClient;
+
+/// HTTP method of a [HttpRequest]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpMethod {
+    /// HTTP GET
+    Get,
+    /// HTTP POST
+    Post,
+    /// HTTP HEAD
+    Head,
+}
+
+impl fmt::Display for HttpMethod {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HttpMethod::Get => write!(formatter, "GET"),
+            HttpMethod::Post => write!(formatter, "POST"),
+            HttpMethod::Head => write!(formatter, "HEAD"),
+        }
+    }
+}
+
+/// HTTP request handed to a [HttpTransport].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    /// [HttpMethod] of the request.
+    method: HttpMethod,
+    /// Absolute url of the request.
+    url: String,
+    /// Header names and values of the request.
+    headers: Vec<(String, String)>,
+    /// Body of the request, empty if none.
+    body: Vec<u8>,
+    /// [Duration] used as timeout for the whole request.
+    timeout: Option<Duration>,
+}
+
+impl HttpRequest {
+    /// Construct a [HttpRequest] without headers, body and timeout.
+    ///
+    /// # Arguments
+    /// * method: the [HttpMethod] to use.
+    /// * url: absolute url to request, e.g. "https://mempool.space/api/blocks/tip/height".
+    pub fn new(method: HttpMethod, url: &str) -> Self {
+        HttpRequest {
+            method,
+            url: String::from(url),
+            headers: Vec::new(),
+            body: Vec::new(),
+            timeout: None,
+        }
+    }
+
+    /// Add a header sent with the request.
+    pub fn set_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((String::from(name), String::from(value)));
+        self
+    }
+
+    /// Set a new body sent with the request.
+    pub fn set_body(mut self, body: Vec<u8>) -> Self {
+        self.body = body;
+        self
+    }
+
+    /// Set a new timeout [Duration] for the request.
+    pub fn set_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Get the [HttpMethod] of the request.
+    pub fn get_method(&self) -> HttpMethod {
+        self.method
+    }
+
+    /// Get a reference to the url of the request.
+    pub fn get_url(&self) -> &str {
+        &self.url
+    }
+
+    /// Get a reference to the header names and values of the request.
+    pub fn get_headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// Get a reference to the body of the request.
+    pub fn get_body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// Get a reference to the timeout [Duration] of the request, if any.
+    pub fn get_timeout(&self) -> Option<&Duration> {
+        self.timeout.as_ref()
+    }
+}
+
+/// HTTP response returned by a [HttpTransport], including non-success responses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    /// Status code of the response.
+    status: u16,
+    /// Header names and values of the response.
+    headers: Vec<(String, String)>,
+    /// Complete body of the response.
+    body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Construct a [HttpResponse] without headers.
+    ///
+    /// # Arguments
+    /// * status: the status code of the response, e.g. 200.
+    /// * body: the complete response body.
+    pub fn new(status: u16, body: Vec<u8>) -> Self {
+        HttpResponse {
+            status,
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    /// Add a header received with the response.
+    pub fn set_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((String::from(name), String::from(value)));
+        self
+    }
+
+    /// Get the status code of the response.
+    pub fn get_status(&self) -> u16 {
+        self.status
+    }
+
+    /// Check if the status code of the response is within 200-299.
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Get the value of the first header with given name, compared case-insensitively.
+    pub fn get_header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Get a reference to the body of the response.
+    pub fn get_body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// Consume the response, returning its body.
+    pub fn into_body(self) -> Vec<u8> {
+        self.body
+    }
+}
+
+/// Trait specifying a transport performing the HTTP requests of a [MempoolClient].
+///
+/// # Example
+/// ```
+/// # use mempool_space::{HttpRequest, HttpResponse, HttpTransport, MempoolClient, MempoolError};
+/// # use std::sync::Arc;
+///
+/// // Transport answering each request with a fixed tip height
+/// #[derive(Debug)]
+/// struct FixedTransport;
+///
+/// impl HttpTransport for FixedTransport {
+///     fn send(&self, _: &HttpRequest) -> Result<HttpResponse, MempoolError> {
+///         Ok(HttpResponse::new(200, b"840000".to_vec()))
+///     }
+/// }
+///
+/// let client = MempoolClient::default().set_transport(Arc::new(FixedTransport));
+/// assert_eq!(client.get_block_tip_height().unwrap(), 840000);
+/// ```
+#[cfg_attr(test, automock)]
+pub trait HttpTransport: Debug + Send + Sync {
+    /// Perform given request.
+    ///
+    /// # Returns
+    /// * On success, the complete [HttpResponse], regardless of its status code.
+    /// * On failure, a [MempoolError::Transport] if the request could not be performed.
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse, MempoolError>;
+}
+
+/// [HttpTransport] based on the blocking client of reqwest.
+#[cfg(feature = "transport-reqwest")]
+#[derive(Debug, Default)]
+pub struct ReqwestTransport {
+    /// Underlying HTTP client.
+    client: reqwest::blocking::Client,
+}
+
+#[cfg(feature = "transport-reqwest")]
+impl ReqwestTransport {
+    /// Construct a [ReqwestTransport] from a configured reqwest client.
+    pub fn new(client: reqwest::blocking::Client) -> Self {
+        ReqwestTransport { client }
+    }
+}
+
+#[cfg(feature = "transport-reqwest")]
+impl HttpTransport for ReqwestTransport {
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse, MempoolError> {
+        let method = match request.get_method() {
+            HttpMethod::Get => reqwest::Method::GET,
+            HttpMethod::Post => reqwest::Method::POST,
+            HttpMethod::Head => reqwest::Method::HEAD,
+        };
+        let mut builder = self.client.request(method, request.get_url());
+        for (name, value) in request.get_headers() {
+            builder = builder.header(name, value);
+        }
+        if let Some(timeout) = request.get_timeout() {
+            builder = builder.timeout(*timeout);
+        }
+        if !request.get_body().is_empty() {
+            builder = builder.body(request.get_body().to_vec());
+        }
+
+        let response = builder.send()?;
+        let mut http_response = HttpResponse::new(response.status().as_u16(), Vec::new());
+        for (name, value) in response.headers() {
+            if let Ok(value) = value.to_str() {
+                http_response = http_response.set_header(name.as_str(), value);
+            }
+        }
+        http_response.body = response.bytes()?.to_vec();
+        Ok(http_response)
+    }
+}
+
+/// [HttpTransport] based on ureq, not depending on an async runtime.
+#[cfg(feature = "transport-ureq")]
+#[derive(Debug)]
+pub struct UreqTransport {
+    /// Underlying HTTP agent.
+    agent: ureq::Agent,
+}
+
+#[cfg(feature = "transport-ureq")]
+impl UreqTransport {
+    /// Construct a [UreqTransport] from a configured ureq agent.
+    pub fn new(agent: ureq::Agent) -> Self {
+        UreqTransport { agent }
+    }
+}
+
+#[cfg(feature = "transport-ureq")]
+impl Default for UreqTransport {
+    fn default() -> Self {
+        UreqTransport::new(ureq::Agent::new())
+    }
+}
+
+#[cfg(feature = "transport-ureq")]
+impl HttpTransport for UreqTransport {
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse, MempoolError> {
+        let method = request.get_method().to_string();
+        let mut builder = self.agent.request(&method, request.get_url());
+        for (name, value) in request.get_headers() {
+            builder = builder.set(name, value);
+        }
+        if let Some(timeout) = request.get_timeout() {
+            builder = builder.timeout(*timeout);
+        }
+
+        // Note: ureq reports non-success status codes as error, hand them out as response
+        let response = match builder.send_bytes(request.get_body()) {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+            Err(error) => return Err(MempoolError::from(error)),
+        };
+        let mut http_response = HttpResponse::new(response.status(), Vec::new());
+        for name in response.headers_names() {
+            if let Some(value) = response.header(&name) {
+                http_response = http_response.set_header(&name, value);
+            }
+        }
+        let mut reader = response.into_reader();
+        std::io::Read::read_to_end(&mut reader, &mut http_response.body)
+            .map_err(|error| MempoolError::Transport("Failed to read response", Box::new(error)))?;
+        Ok(http_response)
+    }
+}
+
+/// Trait specifying a transport performing the HTTP requests of an [AsyncMempoolClient].
+///
+/// # Notes
+/// Requires crate to be configured with feature "async".
+#[cfg(feature = "async")]
+#[cfg_attr(test, automock)]
+pub trait AsyncHttpTransport: Debug + Send + Sync {
+    /// Perform given request.
+    ///
+    /// # Returns
+    /// Future resolving to the complete [HttpResponse], regardless of its status code, or to
+    /// a [MempoolError::Transport] if the request could not be performed.
+    fn send(&self, request: &HttpRequest)
+        -> BoxFuture<'static, Result<HttpResponse, MempoolError>>;
+}
+
+/// [AsyncHttpTransport] based on the async client of reqwest.
+#[cfg(all(feature = "async", feature = "transport-reqwest"))]
+#[derive(Debug, Default)]
+pub struct AsyncReqwestTransport {
+    /// Underlying HTTP client.
+    client: reqwest::Client,
+}
+
+#[cfg(all(feature = "async", feature = "transport-reqwest"))]
+impl AsyncReqwestTransport {
+    /// Construct an [AsyncReqwestTransport] from a configured reqwest client.
+    pub fn new(client: reqwest::Client) -> Self {
+        AsyncReqwestTransport { client }
+    }
+}
+
+#[cfg(all(feature = "async", feature = "transport-reqwest"))]
+impl AsyncHttpTransport for AsyncReqwestTransport {
+    fn send(
+        &self,
+        request: &HttpRequest,
+    ) -> BoxFuture<'static, Result<HttpResponse, MempoolError>> {
+        let method = match request.get_method() {
+            HttpMethod::Get => reqwest::Method::GET,
+            HttpMethod::Post => reqwest::Method::POST,
+            HttpMethod::Head => reqwest::Method::HEAD,
+        };
+        let mut builder = self.client.request(method, request.get_url());
+        for (name, value) in request.get_headers() {
+            builder = builder.header(name, value);
+        }
+        if let Some(timeout) = request.get_timeout() {
+            builder = builder.timeout(*timeout);
+        }
+        if !request.get_body().is_empty() {
+            builder = builder.body(request.get_body().to_vec());
+        }
+
+        async move {
+            let response = builder.send().await?;
+            let mut http_response = HttpResponse::new(response.status().as_u16(), Vec::new());
+            for (name, value) in response.headers() {
+                if let Ok(value) = value.to_str() {
+                    http_response = http_response.set_header(name.as_str(), value);
+                }
+            }
+            http_response.body = response.bytes().await?.to_vec();
+            Ok(http_response)
+        }
+        .boxed()
+    }
+}
+
+/// [AsyncHttpTransport] performing each request via a blocking [HttpTransport] on a
+/// separate thread of the tokio runtime.
+///
+/// # Notes
+/// Intended for blocking transports without an async counterpart, e.g. ureq. The blocking
+/// client of reqwest must not be dropped within an async context.
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct BlockingTransport {
+    /// Underlying blocking transport.
+    transport: Arc<dyn HttpTransport>,
+}
+
+#[cfg(feature = "async")]
+impl BlockingTransport {
+    /// Construct a [BlockingTransport] offloading requests onto given [HttpTransport].
+    pub fn new(transport: Arc<dyn HttpTransport>) -> Self {
+        BlockingTransport { transport }
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncHttpTransport for BlockingTransport {
+    fn send(
+        &self,
+        request: &HttpRequest,
+    ) -> BoxFuture<'static, Result<HttpResponse, MempoolError>> {
+        let transport = self.transport.clone();
+        let request = request.clone();
+        async move {
+            tokio::task::spawn_blocking(move || transport.send(&request))
+                .await
+                .map_err(|error| {
+                    MempoolError::Transport("Failed to join transport thread", Box::new(error))
+                })?
+        }
+        .boxed()
+    }
+}
+
+/// Construct the [HttpTransport] used by default, preferring reqwest over ureq.
+#[cfg(feature = "transport-reqwest")]
+pub(crate) fn default_transport() -> Arc<dyn HttpTransport> {
+    Arc::new(ReqwestTransport::default())
+}
+
+/// Construct the [HttpTransport] used by default, preferring reqwest over ureq.
+#[cfg(all(feature = "transport-ureq", not(feature = "transport-reqwest")))]
+pub(crate) fn default_transport() -> Arc<dyn HttpTransport> {
+    Arc::new(UreqTransport::default())
+}
+
+/// Construct the [AsyncHttpTransport] used by default, preferring reqwest over offloading
+/// the blocking ureq transport.
+#[cfg(all(feature = "async", feature = "transport-reqwest"))]
+pub(crate) fn default_async_transport() -> Arc<dyn AsyncHttpTransport> {
+    Arc::new(AsyncReqwestTransport::default())
+}
+
+/// Construct the [AsyncHttpTransport] used by default, preferring reqwest over offloading
+/// the blocking ureq transport.
+#[cfg(all(
+    feature = "async",
+    feature = "transport-ureq",
+    not(feature = "transport-reqwest")
+))]
+pub(crate) fn default_async_transport() -> Arc<dyn AsyncHttpTransport> {
+    Arc::new(BlockingTransport::new(default_transport()))
+}
+
+#[cfg(not(any(feature = "transport-reqwest", feature = "transport-ureq")))]
+compile_error!("Either feature \"transport-reqwest\" or \"transport-ureq\" must be enabled");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{closed_addr, response, serve};
+
+    fn transports() -> Vec<Box<dyn HttpTransport>> {
+        vec![
+            #[cfg(feature = "transport-reqwest")]
+            Box::<ReqwestTransport>::default(),
+            #[cfg(feature = "transport-ureq")]
+            Box::<UreqTransport>::default(),
+        ]
+    }
+
+    #[test]
+    fn http_response_get_header() {
+        // Expectency: Headers are looked up case-insensitively.
+        let response = HttpResponse::new(429, Vec::new()).set_header("Retry-After", "12");
+        assert_eq!(response.get_header("retry-after"), Some("12"));
+        assert_eq!(response.get_header("Content-Type"), None);
+        assert!(!response.is_success());
+    }
+
+    #[test]
+    fn transports_send() {
+        // Expectency: Each transport sends method, headers and body and returns
+        //             non-success responses instead of failing.
+        for transport in transports() {
+            let (base_url, srv) = serve(vec![
+                response(200, "840000"),
+                "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 12\r\nContent-Length: 0\r\n\
+                 Connection: close\r\n\r\n"
+                    .to_string(),
+            ]);
+            let request = HttpRequest::new(HttpMethod::Post, &format!("{}/tx", base_url))
+                .set_header("Content-Type", "text/plain")
+                .set_body(b"0100".to_vec())
+                .set_timeout(Duration::from_secs(5));
+            let response = transport.send(&request).unwrap();
+            assert_eq!(response.get_status(), 200);
+            assert_eq!(response.into_body(), b"840000");
+
+            let request = HttpRequest::new(HttpMethod::Get, &format!("{}/tip", base_url));
+            let response = transport.send(&request).unwrap();
+            assert_eq!(response.get_status(), 429);
+            assert_eq!(response.get_header("retry-after"), Some("12"));
+
+            let requests = srv.join().unwrap();
+            assert!(requests[0].starts_with("POST /tx HTTP/1.1"));
+            assert!(requests[0]
+                .to_lowercase()
+                .contains("content-type: text/plain"));
+            assert!(requests[0].ends_with("0100"));
+            assert!(requests[1].starts_with("GET /tip HTTP/1.1"));
+        }
+    }
+
+    #[cfg(all(feature = "async", feature = "transport-reqwest"))]
+    #[tokio::test]
+    async fn async_reqwest_transport_send() {
+        // Expectency: The async reqwest transport sends method, headers and body like its
+        //             blocking counterpart.
+        let (base_url, srv) = serve(vec![response(200, "840000")]);
+        let request = HttpRequest::new(HttpMethod::Post, &format!("{}/tx", base_url))
+            .set_header("Content-Type", "text/plain")
+            .set_body(b"0100".to_vec());
+        let response = AsyncReqwestTransport::default()
+            .send(&request)
+            .await
+            .unwrap();
+        assert_eq!(response.get_status(), 200);
+        assert_eq!(response.into_body(), b"840000");
+
+        let requests = srv.join().unwrap();
+        assert!(requests[0].starts_with("POST /tx HTTP/1.1"));
+        assert!(requests[0].ends_with("0100"));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn blocking_transport_send() {
+        // Expectency: Requests are handed unchanged to the wrapped blocking transport.
+        let mut transport = MockHttpTransport::new();
+        transport
+            .expect_send()
+            .withf(|request| request.get_url() == "http://mock/tip")
+            .times(1)
+            .returning(|_| Ok(HttpResponse::new(200, b"840000".to_vec())));
+        let transport = BlockingTransport::new(Arc::new(transport));
+        let request = HttpRequest::new(HttpMethod::Get, "http://mock/tip");
+        let response = transport.send(&request).await.unwrap();
+        assert_eq!(response.into_body(), b"840000");
+    }
+
+    #[test]
+    fn transports_unreachable() {
+        // Expectency: Connection failures are reported as transport error.
+        for transport in transports() {
+            let url = format!("http://{}/tip", closed_addr());
+            let request = HttpRequest::new(HttpMethod::Get, &url);
+            let error = transport.send(&request).unwrap_err();
+            assert!(matches!(error, MempoolError::Transport(_, _)));
+        }
+    }
+}